@@ -1,5 +1,7 @@
 #![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
 #![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
+#![allow(non_snake_case, clippy::type_complexity)]
+#![recursion_limit = "256"]
 
 #[macro_use]
 extern crate alloc;
@@ -7,11 +9,58 @@ extern crate alloc;
 use alloc::{string::String, vec::Vec};
 use alloy_sol_types::sol;
 use stylus_sdk::{
+    abi::ConstString,
     alloy_primitives::{aliases::U8, Address, FixedBytes, U256},
-    crypto, evm,
+    crypto,
     prelude::*,
+    storage::StorageVec,
 };
 
+/// Declare the structs returned by views. Each is a `sol!` struct returned
+/// in memory and declared in the `export-abi` interface.
+macro_rules! view_structs {
+    ($(struct $name:ident { $($ty:ident $field:ident;)* })*) => {
+        sol! {
+            $(
+                #[derive(Default)]
+                struct $name { $($ty $field;)* }
+            )*
+        }
+
+        $(
+            impl stylus_sdk::abi::AbiType for $name {
+                type SolType = Self;
+
+                const ABI: ConstString = ConstString::new(stringify!($name));
+
+                const EXPORT_ABI_ARG: ConstString =
+                    ConstString::new(concat!(stringify!($name), " calldata"));
+
+                const EXPORT_ABI_RET: ConstString =
+                    ConstString::new(concat!(stringify!($name), " memory"));
+            }
+
+            #[cfg(feature = "export-abi")]
+            impl stylus_sdk::abi::export::internal::InnerTypes for $name {
+                fn inner_types() -> Vec<stylus_sdk::abi::export::internal::InnerType> {
+                    let declaration = concat!(
+                        "struct ",
+                        stringify!($name),
+                        " {",
+                        $(" ", stringify!($ty), " ", stringify!($field), ";",)*
+                        " }"
+                    );
+
+                    vec![stylus_sdk::abi::export::internal::InnerType {
+                        name: String::from(declaration),
+                        id: core::any::TypeId::of::<$name>(),
+                    }]
+                }
+            }
+        )*
+    };
+}
+
 sol! {
     enum UserRole {
         Creator,
@@ -19,14 +68,35 @@ sol! {
         Crowdfunder
     }
 
+    enum PaymentIntentStatus {
+        Active,
+        Expired,
+        Exhausted,
+        Cancelled
+    }
+
+    enum InvoiceStatus {
+        Open,
+        PartiallyPaid,
+        Paid,
+        Overdue,
+        Void
+    }
+
+    enum OverpaymentPolicy {
+        Reject,
+        Refund,
+        Tip
+    }
+
     // Events
     event UserRegistered(address indexed user, string handle, uint8 role);
     event PageUpdated(address indexed owner, string handle);
     event PageMetadataUpdated(address indexed owner, string handle, string metadataHash);
     event GoalUpdated(address indexed owner, uint256 goal);
-    event DonationMade(address indexed from, address indexed to, uint256 amount, string messageHash);
-    event ProductPurchased(address indexed buyer, address indexed seller, uint256 productId, uint256 amount);
-    event CampaignContribution(address indexed from, address indexed to, uint256 amount, string messageHash);
+    event DonationMade(address indexed from, address indexed to, uint256 amount, string messageHash, string message, string displayName);
+    event ProductPurchased(address indexed buyer, address indexed seller, uint256 productId, uint256 amount, uint256 tip, bytes32 orderReference);
+    event CampaignContribution(address indexed from, address indexed to, uint256 amount, string messageHash, string message, string displayName);
     event ProductCreated(address indexed owner, uint256 productId, string name, uint256 price);
     event ProductUpdated(address indexed owner, uint256 productId, string metadataHash);
     event ProductDeleted(address indexed owner, uint256 productId);
@@ -37,8 +107,40 @@ sol! {
     event PlatformFeeUpdated(uint256 newFee);
     event PlatformFeesWithdrawn(uint256 amount);
     event PaymentIntentCreated(bytes32 indexed intentId, address indexed creator, string handle, uint256 amount);
-    event PaymentIntentPaid(bytes32 indexed intentId, address indexed payer, uint256 amount);
+    event PaymentIntentPaid(bytes32 indexed intentId, address indexed payer, uint256 amount, bytes32 orderReference);
     event PaymentIntentCancelled(bytes32 indexed intentId);
+    event PaymentIntentUpdated(bytes32 indexed intentId, uint256 expiresAt, uint256 maxUsages, bool isActive, string description);
+    event HandleCommitted(address indexed committer, bytes32 indexed commitment);
+    event HandleCommitConfigUpdated(uint256 minDelay, uint256 maxAge, bool plainRegistrationEnabled);
+    event HandleChanged(address indexed owner, string oldHandle, string newHandle);
+    event HandleReleased(address indexed owner, string handle);
+    event PageTransferStarted(address indexed owner, address indexed newOwner, string handle);
+    event PageTransferred(address indexed previousOwner, address indexed newOwner, string handle);
+    event PageDeactivated(address indexed owner, string handle);
+    event PageReactivated(address indexed owner, string handle);
+    event PageSuspended(address indexed owner, address indexed moderator, string handle, bytes32 reasonHash);
+    event PageUnsuspended(address indexed owner, address indexed moderator, string handle);
+    event ModeratorUpdated(address indexed moderator, bool enabled);
+    event PageCapabilitiesUpdated(address indexed owner, string handle, uint8 capabilities);
+    event OperatorAdded(address indexed owner, address indexed operator, string handle, uint8 permissions);
+    event OperatorRemoved(address indexed owner, address indexed operator, string handle);
+    event RevenueSplitsUpdated(address indexed owner, string handle, uint256 recipientCount);
+    event RevenueSplitsChangeScheduled(address indexed owner, string handle, uint256 recipientCount, uint256 availableAt);
+    event SplitPaid(address indexed owner, address indexed recipient, uint256 amount);
+    event BalanceWithdrawn(address indexed account, uint256 amount);
+    event PayoutAddressChanged(address indexed owner, string handle, address payoutAddress);
+    event PayoutAddressChangeScheduled(address indexed owner, string handle, address payoutAddress, uint256 availableAt);
+    event PayoutTimelockUpdated(address indexed owner, string handle, uint256 timelock);
+    event AffiliateCommissionUpdated(address indexed owner, uint256 productId, bool storewide, uint256 bps);
+    event IntegratorFeePaid(address indexed integrator, address indexed recipient, uint256 amount);
+    event MaxIntegratorFeeUpdated(uint256 newMaxFee);
+    event InvoiceCreated(uint256 indexed invoiceId, address indexed business, address indexed payer, uint256 total, uint256 dueDate);
+    event InvoicePaid(uint256 indexed invoiceId, address indexed payer, uint256 amount, uint256 remaining);
+    event InvoiceVoided(uint256 indexed invoiceId);
+    event AffiliatePaid(address indexed affiliate, address indexed business, uint256 productId, uint256 amount);
+    event OverpaymentPolicyUpdated(address indexed owner, uint8 policy);
+    event CampaignDeadlineUpdated(address indexed owner, uint256 deadline);
+    event LeaderboardChanged(address indexed owner, address indexed supporter, uint256 rank, uint256 total);
 
     // Errors
     error PageNotFound();
@@ -60,6 +162,36 @@ sol! {
     error PaymentIntentInactive();
     error PaymentIntentMaxUsages();
     error InvalidExpiration();
+    error HandleCommitmentNotFound();
+    error HandleCommitmentTooRecent();
+    error HandleCommitmentExpired();
+    error InvalidCommitConfig();
+    error NoPendingTransfer();
+    error InvalidAddress();
+    error PageUnderSuspension();
+    error NotModerator();
+    error CapabilityNotEnabled();
+    error InvalidCapabilities();
+    error InvalidPermissions();
+    error OperatorNotFound();
+    error TooManyOperators();
+    error InvalidSplits();
+    error InvalidTimelock();
+    error NoPendingPayoutChange();
+    error PayoutTimelockActive();
+    error InvalidCommission();
+    error IntegratorFeeTooHigh();
+    error PaymentIntentAlreadyExists();
+    error PaymentIntentCapReached();
+    error InvoiceNotFound();
+    error InvoiceNotPayable();
+    error NotInvoicePayer();
+    error InvalidInvoice();
+    error InvalidMaxUsages();
+    error ReferenceAlreadyUsed();
+    error InvalidOverpaymentPolicy();
+    error MessageTooLong();
+    error InvalidRange();
 }
 
 #[derive(SolidityError)]
@@ -83,6 +215,216 @@ pub enum OnClickContractError {
     PaymentIntentInactive(PaymentIntentInactive),
     PaymentIntentMaxUsages(PaymentIntentMaxUsages),
     InvalidExpiration(InvalidExpiration),
+    HandleCommitmentNotFound(HandleCommitmentNotFound),
+    HandleCommitmentTooRecent(HandleCommitmentTooRecent),
+    HandleCommitmentExpired(HandleCommitmentExpired),
+    InvalidCommitConfig(InvalidCommitConfig),
+    NoPendingTransfer(NoPendingTransfer),
+    InvalidAddress(InvalidAddress),
+    PageUnderSuspension(PageUnderSuspension),
+    NotModerator(NotModerator),
+    CapabilityNotEnabled(CapabilityNotEnabled),
+    InvalidCapabilities(InvalidCapabilities),
+    InvalidPermissions(InvalidPermissions),
+    OperatorNotFound(OperatorNotFound),
+    TooManyOperators(TooManyOperators),
+    InvalidSplits(InvalidSplits),
+    InvalidTimelock(InvalidTimelock),
+    NoPendingPayoutChange(NoPendingPayoutChange),
+    PayoutTimelockActive(PayoutTimelockActive),
+    InvalidCommission(InvalidCommission),
+    IntegratorFeeTooHigh(IntegratorFeeTooHigh),
+    PaymentIntentAlreadyExists(PaymentIntentAlreadyExists),
+    PaymentIntentCapReached(PaymentIntentCapReached),
+    InvoiceNotFound(InvoiceNotFound),
+    InvoiceNotPayable(InvoiceNotPayable),
+    NotInvoicePayer(NotInvoicePayer),
+    InvalidInvoice(InvalidInvoice),
+    InvalidMaxUsages(InvalidMaxUsages),
+    ReferenceAlreadyUsed(ReferenceAlreadyUsed),
+    InvalidOverpaymentPolicy(InvalidOverpaymentPolicy),
+    MessageTooLong(MessageTooLong),
+    InvalidRange(InvalidRange),
+}
+
+// View structs
+view_structs! {
+    struct PageView {
+        address owner;
+        string handle;
+        uint8 role;
+        string name;
+        string metadataHash;
+        uint256 goal;
+        uint256 raised;
+        uint256 supporters;
+        bool isActive;
+        uint256 createdAt;
+    }
+
+    struct ProductView {
+        uint256 id;
+        address businessOwner;
+        string name;
+        uint256 price;
+        string metadataHash;
+        bool isActive;
+        uint256 totalSold;
+        uint256 createdAt;
+    }
+
+    struct PaymentIntentView {
+        address creator;
+        string handle;
+        uint256 amount;
+        string description;
+        bool isActive;
+        uint256 createdAt;
+        uint256 expiresAt;
+        uint256 usageCount;
+        uint256 maxUsages;
+    }
+
+    struct TransactionView {
+        uint256 id;
+        address from; // zero for anonymous supporters
+        address to;
+        uint256 amount;
+        string messageHash;
+        uint8 recipientRole;
+        uint256 productId;
+        uint256 timestamp;
+        bool isRefunded;
+        bytes32 orderReference;
+        uint256 tip;
+        string message;
+        string displayName;
+        bool isAnonymous;
+    }
+}
+
+/// Default minimum age of a handle commitment before it can be revealed (seconds)
+const DEFAULT_HANDLE_COMMIT_MIN_DELAY: u64 = 60;
+/// Default maximum age of a handle commitment before it must be renewed (seconds)
+const DEFAULT_HANDLE_COMMIT_MAX_AGE: u64 = 86_400;
+
+/// Page capability: accept donations/tips
+const CAPABILITY_TIPS: u8 = 1 << 0;
+/// Page capability: sell products
+const CAPABILITY_PRODUCTS: u8 = 1 << 1;
+/// Page capability: run a crowdfunding campaign
+const CAPABILITY_CAMPAIGNS: u8 = 1 << 2;
+const ALL_CAPABILITIES: u8 = CAPABILITY_TIPS | CAPABILITY_PRODUCTS | CAPABILITY_CAMPAIGNS;
+
+/// Operator permission: none, reserved for actions only the page owner can take
+const OWNER_ONLY: u8 = 0;
+/// Operator permission: create, update and delete products
+const PERMISSION_PRODUCTS: u8 = 1 << 0;
+/// Operator permission: create and cancel payment intents
+const PERMISSION_INTENTS: u8 = 1 << 1;
+/// Operator permission: edit page name, metadata and goal
+const PERMISSION_METADATA: u8 = 1 << 2;
+const ALL_PERMISSIONS: u8 = PERMISSION_PRODUCTS | PERMISSION_INTENTS | PERMISSION_METADATA;
+/// Maximum number of operators per page
+const MAX_OPERATORS: usize = 10;
+
+/// Maximum number of revenue split recipients per page
+const MAX_SPLIT_RECIPIENTS: usize = 10;
+
+/// Maximum payout address change timelock (30 days)
+const MAX_PAYOUT_TIMELOCK: u64 = 30 * 86_400;
+
+/// Maximum number of items returned by paginated views
+const MAX_PAGE_SIZE: usize = 100;
+
+/// Maximum number of line items per invoice
+const MAX_INVOICE_ITEMS: usize = 20;
+
+/// Maximum number of days covered by a daily volume query
+const MAX_DAY_RANGE: u64 = 366;
+
+/// Number of top supporters kept on each page's leaderboard
+const MAX_LEADERBOARD_SIZE: usize = 10;
+
+/// Maximum length of an on-chain supporter message (bytes)
+const MAX_MESSAGE_LENGTH: usize = 280;
+
+/// Maximum length of a supporter display name (bytes)
+const MAX_DISPLAY_NAME_LENGTH: usize = 32;
+
+/// Payment intent id: keccak256(pageId || nonce || salt)
+fn payment_intent_id(page_id: U256, nonce: U256, salt: FixedBytes<32>) -> FixedBytes<32> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&page_id.to_be_bytes::<32>());
+    data.extend_from_slice(&nonce.to_be_bytes::<32>());
+    data.extend_from_slice(salt.as_slice());
+
+    crypto::keccak(data)
+}
+
+/// Check the length limits of a supporter message and display name
+fn check_message(message: &str, display_name: &str) -> Result<(), OnClickContractError> {
+    if message.len() > MAX_MESSAGE_LENGTH || display_name.len() > MAX_DISPLAY_NAME_LENGTH {
+        return Err(OnClickContractError::MessageTooLong(MessageTooLong {}));
+    }
+
+    Ok(())
+}
+
+/// Transaction as shown in views, hiding the sender of anonymous supporters
+fn transaction_view(transaction: &Transaction) -> TransactionView {
+    let is_anonymous = transaction.isAnonymous.get();
+    let from = if is_anonymous {
+        Address::ZERO
+    } else {
+        transaction.from.get()
+    };
+
+    TransactionView {
+        id: transaction.id.get(),
+        from,
+        to: transaction.to.get(),
+        amount: transaction.amount.get(),
+        messageHash: transaction.messageHash.get_string(),
+        recipientRole: transaction.recipientRole.get().to::<u8>(),
+        productId: transaction.productId.get(),
+        timestamp: transaction.timestamp.get(),
+        isRefunded: transaction.isRefunded.get(),
+        orderReference: transaction.orderReference.get(),
+        tip: transaction.tip.get(),
+        message: transaction.message.get_string(),
+        displayName: transaction.displayName.get_string(),
+        isAnonymous: is_anonymous,
+    }
+}
+
+/// Replace a split list with (recipient, recipient page id, share in bps) entries
+fn store_splits(splits: &mut StorageVec<RevenueSplit>, entries: &[(Address, Option<U256>, U256)]) {
+    splits.erase();
+
+    for &(recipient, recipient_page, bps) in entries {
+        let mut split = splits.grow();
+        split.recipient.set(recipient);
+        if let Some(recipient_page) = recipient_page {
+            split.isPage.set(true);
+            split.pageId.set(recipient_page);
+        }
+        split.bps.set(bps);
+    }
+}
+
+/// Check whether a page has any of the given capabilities
+fn has_capability(page: &Page, capability: u8) -> bool {
+    page.capabilities.get().to::<u8>() & capability != 0
+}
+
+/// Default capabilities for a registration role
+fn capabilities_for_role(role: u8) -> u8 {
+    match role {
+        0 => CAPABILITY_TIPS,
+        1 => CAPABILITY_PRODUCTS,
+        _ => CAPABILITY_CAMPAIGNS,
+    }
 }
 
 impl From<Vec<u8>> for OnClickContractError {
@@ -92,6 +434,7 @@ impl From<Vec<u8>> for OnClickContractError {
 }
 
 sol_storage! {
+    #[derive(Erase)]
     pub struct Page {
         address owner;
         string handle;
@@ -103,6 +446,17 @@ sol_storage! {
         uint256 supporters;
         bool isActive;
         uint256 createdAt;
+        uint256 id; // stable across handle changes and transfers
+        bool isSuspended;
+        bytes32 suspensionReason;
+        uint8 capabilities; // CAPABILITY_* bit flags
+        uint256 affiliateBps; // storewide affiliate commission
+        address payoutAddress; // zero = owner
+        address pendingPayoutAddress;
+        uint256 payoutChangeAvailableAt; // 0 = no pending change
+        uint256 payoutTimelock; // seconds
+        uint8 overpaymentPolicy; // OverpaymentPolicy, applied to purchases
+        uint256 campaignDeadline; // campaign end time, 0 = none
     }
 }
 
@@ -116,6 +470,8 @@ sol_storage! {
         bool isActive;
         uint256 totalSold;
         uint256 createdAt;
+        uint256 pageId;
+        uint256 affiliateBps; // 0 = storewide rate
     }
 }
 
@@ -129,6 +485,24 @@ sol_storage! {
     }
 }
 
+sol_storage! {
+    pub struct PageStats {
+        uint256 totalRaised; // lifetime net amount received
+        uint256 transactionCount;
+        uint256 totalRevenue; // net amount from product sales and invoices
+        uint256 totalOrders;
+        uint256 activeProducts;
+    }
+}
+
+sol_storage! {
+    pub struct SupporterRecord {
+        uint256 total; // net amount paid
+        uint256 payments;
+        bool isPublic; // made at least one non-anonymous payment
+    }
+}
+
 sol_storage! {
     pub struct Transaction {
         uint256 id;
@@ -140,6 +514,21 @@ sol_storage! {
         uint256 productId;
         uint256 timestamp;
         bool isRefunded;
+        bytes32 orderReference; // merchant order reference, zero if none
+        uint256 tip; // part of the payment given as a tip, before fees
+        string message; // short on-chain message
+        string displayName;
+        bool isAnonymous; // hide the sender in views
+    }
+}
+
+sol_storage! {
+    #[derive(Erase)]
+    pub struct RevenueSplit {
+        address recipient;
+        bool isPage; // if set, pays the current owner of page `pageId`
+        uint256 pageId;
+        uint256 bps;
     }
 }
 
@@ -155,6 +544,38 @@ sol_storage! {
         uint256 expiresAt;
         uint256 usageCount;
         uint256 maxUsages; // 0 = unlimited
+        uint256 pageId;
+        bool openAmount; // payer chooses the amount
+        uint256 minAmount;
+        uint256 maxAmount; // 0 = no maximum
+        uint256 suggestedAmount;
+        uint256 totalCap; // 0 = no cap
+        uint256 totalCollected;
+        uint256 productId;
+        uint256 quantity; // 0 = not linked to a product
+    }
+}
+
+sol_storage! {
+    pub struct Invoice {
+        uint256 id;
+        uint256 pageId;
+        address issuer;
+        address payer; // zero = anyone can pay
+        uint256 total;
+        uint256 paid;
+        uint256 lateFee; // added to the total once overdue
+        uint256 dueDate;
+        uint8 status; // InvoiceStatus, Overdue is derived from dueDate
+        uint256 createdAt;
+    }
+}
+
+sol_storage! {
+    pub struct InvoiceLineItem {
+        bytes32 descriptionHash;
+        uint256 quantity;
+        uint256 unitPrice;
     }
 }
 
@@ -174,16 +595,67 @@ sol_storage! {
         mapping(uint256 => Transaction) transactions;
         mapping(address => uint256[]) userProducts;
         mapping(address => uint256[]) userTransactions;
-        mapping(string => uint256[]) pageTransactions;
+        mapping(uint256 => uint256[]) pageTransactions; // page id => transaction ids
+        mapping(uint256 => mapping(bytes32 => uint256)) pageOrderReferences; // page id => order reference => tx id + 1
         mapping(bytes32 => PaymentIntent) paymentIntents;
-        mapping(string => bytes32[]) handlePaymentIntents;
-        mapping(address => bytes32[]) userPaymentIntents;
+        mapping(uint256 => bytes32[]) pagePaymentIntents;
+        mapping(uint256 => uint256) intentNonces; // page id => next intent nonce
+        mapping(uint256 => address) pageAddresses; // page id => current owner
+        mapping(uint256 => address) pendingPageTransfers;
 
         // Platform settings
         address owner;
         uint256 platformFee; // in basis points (e.g., 250 = 2.5%)
         uint256 platformFeesCollected;
         bool paused;
+
+        // Platform metrics
+        mapping(uint8 => uint256) pagesByRole;
+        mapping(uint8 => uint256) volumeByRole; // gross amount paid to pages of each role
+        uint256 totalFeesCollected; // lifetime platform fees
+        mapping(uint256 => uint256) dailyVolume; // day (timestamp / 86400) => gross amount
+
+        // Handle registration (commit-reveal)
+        mapping(address => mapping(bytes32 => uint256)) handleCommitments; // committer => commitment => committedAt
+        uint256 handleCommitMinDelay; // 0 = default
+        uint256 handleCommitMaxAge; // 0 = default
+        bool plainRegistrationEnabled;
+
+        // Moderation
+        mapping(address => bool) moderators;
+
+        // Page operators
+        mapping(uint256 => address[]) pageOperators;
+        mapping(uint256 => mapping(address => uint8)) operatorPermissions;
+
+        // Revenue splits
+        mapping(uint256 => RevenueSplit[]) pageSplits;
+        mapping(uint256 => RevenueSplit[]) pendingPageSplits; // splits waiting out the payout timelock
+        mapping(uint256 => uint256) splitsChangeAvailableAt; // page id => 0 = no pending change
+        mapping(address => uint256) pendingWithdrawals;
+
+        // Affiliates
+        mapping(address => mapping(uint256 => uint256)) affiliateEarnings; // affiliate => page id => earned
+
+        // Statistics
+        mapping(uint256 => PageStats) pageStats;
+        mapping(uint256 => mapping(uint256 => uint256)) pageDailyRevenue; // page id => day => net amount
+        mapping(uint256 => mapping(uint256 => uint256)) pageDailyPayments; // page id => day => payment count
+
+        // Supporters
+        mapping(uint256 => mapping(address => SupporterRecord)) supporterRecords; // page id => supporter => record
+        mapping(uint256 => address[]) pageSupporters; // in order of first payment
+        mapping(uint256 => address[]) leaderboards; // page id => top public supporters, highest first
+
+        // Integrators
+        uint256 maxIntegratorFee; // in basis points
+
+        // Invoices
+        uint256 invoiceId;
+        mapping(uint256 => Invoice) invoices;
+        mapping(uint256 => InvoiceLineItem[]) invoiceItems;
+        mapping(uint256 => uint256[]) pageInvoices;
+        mapping(address => uint256[]) payerInvoices;
     }
 }
 
@@ -191,13 +663,57 @@ sol_storage! {
 impl OnClickContract {
     // ==================== User & Page Management ====================
 
-    /// Register a new user and create their page
+    /// Commit to a handle before registering it, so the handle is not
+    /// revealed in the mempool. `commitment` is `compute_handle_commitment(handle, sender, salt)`.
+    /// Commitments are stored per sender, so others cannot reset their timestamp.
+    pub fn commit_handle(
+        &mut self,
+        commitment: FixedBytes<32>,
+    ) -> Result<(), OnClickContractError> {
+        let sender = self.vm().msg_sender();
+        let timestamp = U256::from(self.vm().block_timestamp());
+
+        self.handleCommitments
+            .setter(sender)
+            .setter(commitment)
+            .set(timestamp);
+
+        log(
+            self.vm(),
+            HandleCommitted {
+                committer: sender,
+                commitment,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Compute the commitment hash for a handle registration
+    pub fn compute_handle_commitment(
+        &self,
+        handle: String,
+        owner: Address,
+        salt: FixedBytes<32>,
+    ) -> FixedBytes<32> {
+        let mut data = Vec::new();
+        data.extend_from_slice(handle.as_bytes());
+        data.extend_from_slice(owner.as_slice());
+        data.extend_from_slice(salt.as_slice());
+
+        crypto::keccak(data)
+    }
+
+    /// Register a new user and create their page.
+    /// Reveals a prior `commit_handle` commitment made with `salt`; a zero salt
+    /// skips the commitment only while plain registration is enabled.
     pub fn register_user(
         &mut self,
         handle: String,
         role: u8,
         name: String,
         metadata_hash: String,
+        salt: FixedBytes<32>,
     ) -> Result<U256, OnClickContractError> {
         // Validate handle
         if handle.len() < 3 {
@@ -226,6 +742,11 @@ impl OnClickContract {
             return Err(OnClickContractError::InvalidRole(InvalidRole {}));
         }
 
+        // Reveal the handle commitment
+        if salt != FixedBytes::ZERO || !self.plainRegistrationEnabled.get() {
+            self.consume_handle_commitment(&handle, sender, salt)?;
+        }
+
         // Get next page ID
         let page_id = self.pageId.get();
         self.pageId.set(page_id + U256::from(1));
@@ -245,16 +766,26 @@ impl OnClickContract {
         page.supporters.set(U256::ZERO);
         page.isActive.set(true);
         page.createdAt.set(timestamp);
+        page.id.set(page_id);
+        page.capabilities.set(U8::from(capabilities_for_role(role)));
 
-        // Map handle to address
+        // Map handle and page id to address
         self.handleToAddress.setter(handle.clone()).set(sender);
+        self.pageAddresses.setter(page_id).set(sender);
+
+        let mut role_count = self.pagesByRole.setter(U8::from(role));
+        let pages = role_count.get();
+        role_count.set(pages + U256::from(1));
 
         // Emit event
-        evm::log(UserRegistered {
-            user: sender,
-            handle: handle.clone(),
-            role,
-        });
+        log(
+            self.vm(),
+            UserRegistered {
+                user: sender,
+                handle: handle.clone(),
+                role,
+            },
+        );
 
         Ok(page_id)
     }
@@ -265,211 +796,647 @@ impl OnClickContract {
         handle_addr.get() == Address::ZERO
     }
 
-    /// Update page information
-    pub fn update_page_info(
+    /// Change a page's handle, keeping its products and payment intents.
+    /// The new handle is revealed from a `commit_handle` commitment as in
+    /// `register_user`.
+    pub fn change_handle(
         &mut self,
-        handle: String,
-        name: String,
-        metadata_hash: String,
+        old_handle: String,
+        new_handle: String,
+        salt: FixedBytes<32>,
     ) -> Result<(), OnClickContractError> {
-        let sender = self.vm().msg_sender();
-        let page_addr = self.handleToAddress.getter(handle.clone());
-        let owner = page_addr.get();
+        let owner = self.authorize_page(&old_handle, OWNER_ONLY)?;
 
-        if owner == Address::ZERO {
-            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        if self.pages.getter(owner).isSuspended.get() {
+            return Err(OnClickContractError::PageUnderSuspension(
+                PageUnderSuspension {},
+            ));
         }
 
-        if owner != sender {
-            return Err(OnClickContractError::NotPageOwner(NotPageOwner {}));
+        if new_handle.len() < 3 {
+            return Err(OnClickContractError::InvalidHandle(InvalidHandle {}));
         }
 
-        let mut page = self.pages.setter(owner);
-        page.name.set_str(&name);
-        page.metadataHash.set_str(&metadata_hash);
+        if self.handleToAddress.get(new_handle.clone()) != Address::ZERO {
+            return Err(OnClickContractError::HandleAlreadyTaken(
+                HandleAlreadyTaken {},
+            ));
+        }
+
+        // Reveal the handle commitment
+        if salt != FixedBytes::ZERO || !self.plainRegistrationEnabled.get() {
+            self.consume_handle_commitment(&new_handle, owner, salt)?;
+        }
 
-        evm::log(PageUpdated {
-            owner,
-            handle: handle.clone(),
-        });
+        self.handleToAddress.delete(old_handle.clone());
+        self.handleToAddress.setter(new_handle.clone()).set(owner);
+        self.pages.setter(owner).handle.set_str(&new_handle);
 
-        evm::log(PageMetadataUpdated {
-            owner,
-            handle: handle.clone(),
-            metadataHash: metadata_hash,
-        });
+        log(
+            self.vm(),
+            HandleChanged {
+                owner,
+                oldHandle: old_handle,
+                newHandle: new_handle,
+            },
+        );
 
         Ok(())
     }
 
-    /// Set or update funding goal
-    pub fn set_funding_goal(
+    /// Release a handle and close its page so the handle can be registered again.
+    /// Products and payment intents of the page stop resolving. Suspended pages
+    /// cannot be released.
+    pub fn release_handle(&mut self, handle: String) -> Result<(), OnClickContractError> {
+        let owner = self.authorize_page(&handle, OWNER_ONLY)?;
+
+        let page = self.pages.getter(owner);
+        if page.isSuspended.get() {
+            return Err(OnClickContractError::PageUnderSuspension(
+                PageUnderSuspension {},
+            ));
+        }
+        let page_id = page.id.get();
+        let role = page.role.get();
+
+        let mut role_count = self.pagesByRole.setter(role);
+        let pages = role_count.get();
+        role_count.set(pages - U256::from(1));
+
+        self.handleToAddress.delete(handle.clone());
+        self.pageAddresses.delete(page_id);
+        self.pendingPageTransfers.delete(page_id);
+        self.clear_operators(page_id);
+        self.pages.delete(owner);
+
+        log(self.vm(), HandleReleased { owner, handle });
+
+        Ok(())
+    }
+
+    /// Start transferring a page to another address, which must accept it.
+    /// Passing the zero address cancels a pending transfer.
+    pub fn transfer_page(
         &mut self,
         handle: String,
-        goal: U256,
+        new_owner: Address,
     ) -> Result<(), OnClickContractError> {
+        let owner = self.authorize_page(&handle, OWNER_ONLY)?;
+
+        if new_owner == owner {
+            return Err(OnClickContractError::InvalidAddress(InvalidAddress {}));
+        }
+
+        let page_id = self.pages.getter(owner).id.get();
+        self.pendingPageTransfers.setter(page_id).set(new_owner);
+
+        log(
+            self.vm(),
+            PageTransferStarted {
+                owner,
+                newOwner: new_owner,
+                handle,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Accept a pending page transfer; the caller must not already own a page
+    pub fn accept_page_transfer(&mut self, handle: String) -> Result<(), OnClickContractError> {
         let sender = self.vm().msg_sender();
-        let page_addr = self.handleToAddress.getter(handle.clone());
-        let owner = page_addr.get();
+        let owner = self.handleToAddress.get(handle.clone());
 
         if owner == Address::ZERO {
             return Err(OnClickContractError::PageNotFound(PageNotFound {}));
         }
 
-        if owner != sender {
-            return Err(OnClickContractError::NotPageOwner(NotPageOwner {}));
-        }
-
-        let mut page = self.pages.setter(owner);
-        let role = page.role.get();
+        let page_id = self.pages.getter(owner).id.get();
+        let pending = self.pendingPageTransfers.get(page_id);
 
-        // Only Creator (0) and Crowdfunder (2) can have goals
-        if role != U8::from(0) && role != U8::from(2) {
-            return Err(OnClickContractError::InvalidRole(InvalidRole {}));
+        if pending == Address::ZERO || pending != sender {
+            return Err(OnClickContractError::NoPendingTransfer(
+                NoPendingTransfer {},
+            ));
         }
 
-        page.goal.set(goal);
+        if self.pages.getter(sender).owner.get() != Address::ZERO {
+            return Err(OnClickContractError::HandleAlreadyTaken(
+                HandleAlreadyTaken {},
+            ));
+        }
 
-        evm::log(GoalUpdated { owner, goal });
+        self.move_page(owner, sender);
+        self.clear_operators(page_id);
+        self.handleToAddress.setter(handle.clone()).set(sender);
+        self.pageAddresses.setter(page_id).set(sender);
+        self.pendingPageTransfers.delete(page_id);
+
+        log(
+            self.vm(),
+            PageTransferred {
+                previousOwner: owner,
+                newOwner: sender,
+                handle,
+            },
+        );
 
         Ok(())
     }
 
-    /// Get page by handle
-    pub fn get_page_by_handle(
+    /// Get the address a page is pending transfer to (zero if none)
+    pub fn get_pending_page_transfer(
         &self,
         handle: String,
-    ) -> Result<
-        (
-            Address,
-            String,
-            u8,
-            String,
-            String,
-            U256,
-            U256,
-            U256,
-            bool,
-            U256,
-        ),
-        OnClickContractError,
-    > {
-        let page_addr = self.handleToAddress.getter(handle);
-        let owner = page_addr.get();
+    ) -> Result<Address, OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
 
         if owner == Address::ZERO {
             return Err(OnClickContractError::PageNotFound(PageNotFound {}));
         }
 
-        let page = self.pages.getter(owner);
-        Ok((
-            page.owner.get(),
-            page.handle.get_string(),
-            page.role.get().to::<u8>(),
-            page.name.get_string(),
-            page.metadataHash.get_string(),
-            page.goal.get(),
-            page.raised.get(),
-            page.supporters.get(),
-            page.isActive.get(),
-            page.createdAt.get(),
-        ))
+        let page_id = self.pages.getter(owner).id.get();
+        Ok(self.pendingPageTransfers.get(page_id))
     }
 
-    /// Get page by address
-    pub fn get_page_by_address(
-        &self,
-        user: Address,
-    ) -> Result<
-        (
-            Address,
-            String,
-            u8,
-            String,
-            String,
-            U256,
-            U256,
-            U256,
-            bool,
-            U256,
-        ),
-        OnClickContractError,
-    > {
-        let page = self.pages.getter(user);
+    /// Update page information
+    pub fn update_page_info(
+        &mut self,
+        handle: String,
+        name: String,
+        metadata_hash: String,
+    ) -> Result<(), OnClickContractError> {
+        let owner = self.authorize_page(&handle, PERMISSION_METADATA)?;
 
-        if page.owner.get() == Address::ZERO {
-            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
-        }
+        let mut page = self.pages.setter(owner);
+        page.name.set_str(&name);
+        page.metadataHash.set_str(&metadata_hash);
 
-        Ok((
-            page.owner.get(),
-            page.handle.get_string(),
-            page.role.get().to::<u8>(),
-            page.name.get_string(),
-            page.metadataHash.get_string(),
-            page.goal.get(),
-            page.raised.get(),
-            page.supporters.get(),
-            page.isActive.get(),
-            page.createdAt.get(),
-        ))
-    }
+        log(
+            self.vm(),
+            PageUpdated {
+                owner,
+                handle: handle.clone(),
+            },
+        );
+
+        log(
+            self.vm(),
+            PageMetadataUpdated {
+                owner,
+                handle: handle.clone(),
+                metadataHash: metadata_hash,
+            },
+        );
 
-    // ==================== Payment Functions ====================
+        Ok(())
+    }
 
-    /// Make a donation to a Creator
-    #[payable]
-    pub fn make_donation(
+    /// Set or update funding goal
+    pub fn set_funding_goal(
         &mut self,
         handle: String,
-        message_hash: String,
-    ) -> Result<U256, OnClickContractError> {
-        let amount = self.vm().msg_value();
-        if amount == U256::ZERO {
-            return Err(OnClickContractError::InvalidAmount(InvalidAmount {}));
-        }
+        goal: U256,
+    ) -> Result<(), OnClickContractError> {
+        let owner = self.authorize_page(&handle, PERMISSION_METADATA)?;
 
-        let sender = self.vm().msg_sender();
-        let page_addr = self.handleToAddress.getter(handle.clone());
-        let recipient = page_addr.get();
+        let mut page = self.pages.setter(owner);
 
-        if recipient == Address::ZERO {
-            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        // Only pages taking tips or running campaigns can have goals
+        if !has_capability(&page, CAPABILITY_TIPS | CAPABILITY_CAMPAIGNS) {
+            return Err(OnClickContractError::CapabilityNotEnabled(
+                CapabilityNotEnabled {},
+            ));
         }
 
-        let mut page = self.pages.setter(recipient);
-
-        if !page.isActive.get() {
-            return Err(OnClickContractError::PageNotActive(PageNotActive {}));
-        }
+        page.goal.set(goal);
 
-        if page.role.get() != U8::from(0) {
-            // 0 = Creator
-            return Err(OnClickContractError::InvalidRole(InvalidRole {}));
-        }
+        log(self.vm(), GoalUpdated { owner, goal });
 
-        // Calculate platform fee
-        let fee = (amount * self.platformFee.get()) / U256::from(10000);
-        let net_amount = amount - fee;
+        Ok(())
+    }
 
-        // Update page stats
-        let raised = page.raised.get();
-        page.raised.set(raised + net_amount);
-        let supporters = page.supporters.get();
-        page.supporters.set(supporters + U256::from(1));
+    /// Set a campaign's end time, reported as time left by `get_campaign_progress`
+    /// (0 = none). Contributions are still accepted after it.
+    pub fn set_campaign_deadline(
+        &mut self,
+        handle: String,
+        deadline: U256,
+    ) -> Result<(), OnClickContractError> {
+        let owner = self.authorize_page(&handle, PERMISSION_METADATA)?;
 
-        // Transfer funds
-        self.vm().transfer_eth(recipient, net_amount)?;
+        if !has_capability(&self.pages.getter(owner), CAPABILITY_CAMPAIGNS) {
+            return Err(OnClickContractError::CapabilityNotEnabled(
+                CapabilityNotEnabled {},
+            ));
+        }
 
-        // Update platform fees
-        let fees = self.platformFeesCollected.get();
-        self.platformFeesCollected.set(fees + fee);
+        let timestamp = U256::from(self.vm().block_timestamp());
+        if deadline != U256::ZERO && deadline <= timestamp {
+            return Err(OnClickContractError::InvalidExpiration(
+                InvalidExpiration {},
+            ));
+        }
+
+        self.pages.setter(owner).campaignDeadline.set(deadline);
+
+        log(self.vm(), CampaignDeadlineUpdated { owner, deadline });
+
+        Ok(())
+    }
+
+    /// Get page by handle
+    pub fn get_page_by_handle(&self, handle: String) -> Result<PageView, OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
+        self.get_page_by_address(owner)
+    }
+
+    /// Get page by address
+    pub fn get_page_by_address(&self, user: Address) -> Result<PageView, OnClickContractError> {
+        let page = self.pages.getter(user);
+
+        if page.owner.get() == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        Ok(PageView {
+            owner: page.owner.get(),
+            handle: page.handle.get_string(),
+            role: page.role.get().to::<u8>(),
+            name: page.name.get_string(),
+            metadataHash: page.metadataHash.get_string(),
+            goal: page.goal.get(),
+            raised: page.raised.get(),
+            supporters: page.supporters.get(),
+            isActive: page.isActive.get(),
+            createdAt: page.createdAt.get(),
+        })
+    }
+
+    /// Get pages for several handles. Each entry is a found flag and the page
+    /// (zeroed when not found).
+    pub fn get_pages_by_handles(&self, handles: Vec<String>) -> Vec<(bool, PageView)> {
+        let mut result = Vec::new();
+
+        for handle in handles {
+            match self.get_page_by_handle(handle) {
+                Ok(page) => result.push((true, page)),
+                Err(_) => result.push((false, PageView::default())),
+            }
+        }
+
+        result
+    }
+
+    /// Get page capabilities as CAPABILITY_* bit flags (1 = tips, 2 = products, 4 = campaigns)
+    pub fn get_page_capabilities(&self, handle: String) -> Result<u8, OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
+
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        Ok(self.pages.getter(owner).capabilities.get().to::<u8>())
+    }
+
+    /// Set which kinds of payments a page accepts. The registration role is kept for reads.
+    pub fn set_page_capabilities(
+        &mut self,
+        handle: String,
+        capabilities: u8,
+    ) -> Result<(), OnClickContractError> {
+        let owner = self.authorize_page(&handle, OWNER_ONLY)?;
+
+        if capabilities == 0 || capabilities & !ALL_CAPABILITIES != 0 {
+            return Err(OnClickContractError::InvalidCapabilities(
+                InvalidCapabilities {},
+            ));
+        }
+
+        self.pages
+            .setter(owner)
+            .capabilities
+            .set(U8::from(capabilities));
+
+        log(
+            self.vm(),
+            PageCapabilitiesUpdated {
+                owner,
+                handle,
+                capabilities,
+            },
+        );
+
+        Ok(())
+    }
+
+    // ==================== Page Operators ====================
+
+    /// Add an operator to a page, or update an existing operator's permissions.
+    /// `permissions` is a set of PERMISSION_* flags (1 = products, 2 = intents, 4 = metadata);
+    /// operators can never withdraw funds or change page ownership.
+    pub fn add_operator(
+        &mut self,
+        handle: String,
+        operator: Address,
+        permissions: u8,
+    ) -> Result<(), OnClickContractError> {
+        let owner = self.authorize_page(&handle, OWNER_ONLY)?;
+
+        if operator == Address::ZERO || operator == owner {
+            return Err(OnClickContractError::InvalidAddress(InvalidAddress {}));
+        }
+
+        if permissions == 0 || permissions & !ALL_PERMISSIONS != 0 {
+            return Err(OnClickContractError::InvalidPermissions(
+                InvalidPermissions {},
+            ));
+        }
+
+        let page_id = self.pages.getter(owner).id.get();
+        let is_new = self.operatorPermissions.getter(page_id).get(operator) == U8::ZERO;
+
+        if is_new {
+            let mut operators = self.pageOperators.setter(page_id);
+            if operators.len() >= MAX_OPERATORS {
+                return Err(OnClickContractError::TooManyOperators(TooManyOperators {}));
+            }
+            operators.push(operator);
+        }
+
+        self.operatorPermissions
+            .setter(page_id)
+            .setter(operator)
+            .set(U8::from(permissions));
+
+        log(
+            self.vm(),
+            OperatorAdded {
+                owner,
+                operator,
+                handle,
+                permissions,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Remove an operator from a page
+    pub fn remove_operator(
+        &mut self,
+        handle: String,
+        operator: Address,
+    ) -> Result<(), OnClickContractError> {
+        let owner = self.authorize_page(&handle, OWNER_ONLY)?;
+        let page_id = self.pages.getter(owner).id.get();
+
+        if self.operatorPermissions.getter(page_id).get(operator) == U8::ZERO {
+            return Err(OnClickContractError::OperatorNotFound(OperatorNotFound {}));
+        }
+
+        self.remove_operator_entry(page_id, operator);
+
+        log(
+            self.vm(),
+            OperatorRemoved {
+                owner,
+                operator,
+                handle,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Get a page's operators and their permission flags
+    pub fn get_operators(
+        &self,
+        handle: String,
+    ) -> Result<Vec<(Address, u8)>, OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
+
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        let page_id = self.pages.getter(owner).id.get();
+        let operators = self.pageOperators.getter(page_id);
+        let permissions = self.operatorPermissions.getter(page_id);
+        let mut result = Vec::new();
+
+        for i in 0..operators.len() {
+            let operator = operators.get(i).unwrap();
+            result.push((operator, permissions.get(operator).to::<u8>()));
+        }
+
+        Ok(result)
+    }
+
+    /// Get an operator's permission flags on a page (0 if not an operator)
+    pub fn get_operator_permissions(
+        &self,
+        handle: String,
+        operator: Address,
+    ) -> Result<u8, OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
+
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        let page_id = self.pages.getter(owner).id.get();
+        Ok(self
+            .operatorPermissions
+            .getter(page_id)
+            .get(operator)
+            .to::<u8>())
+    }
+
+    /// Get page status (is active, is suspended, suspension reason hash)
+    pub fn get_page_status(
+        &self,
+        handle: String,
+    ) -> Result<(bool, bool, FixedBytes<32>), OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
+
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        let page = self.pages.getter(owner);
+        Ok((
+            page.isActive.get(),
+            page.isSuspended.get(),
+            page.suspensionReason.get(),
+        ))
+    }
+
+    /// Deactivate own page; it stops accepting payments until reactivated
+    pub fn deactivate_page(&mut self, handle: String) -> Result<(), OnClickContractError> {
+        self.set_page_active(handle, false)
+    }
+
+    /// Reactivate own page
+    pub fn reactivate_page(&mut self, handle: String) -> Result<(), OnClickContractError> {
+        self.set_page_active(handle, true)
+    }
+
+    // ==================== Moderation ====================
+
+    /// Suspend a page (moderators only). Incoming payments are blocked, but
+    /// the owner can still withdraw funds already earned.
+    pub fn suspend_page(
+        &mut self,
+        handle: String,
+        reason_hash: FixedBytes<32>,
+    ) -> Result<(), OnClickContractError> {
+        let sender = self.vm().msg_sender();
+        if !self.is_moderator(sender) {
+            return Err(OnClickContractError::NotModerator(NotModerator {}));
+        }
+
+        let owner = self.handleToAddress.get(handle.clone());
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        let mut page = self.pages.setter(owner);
+        page.isSuspended.set(true);
+        page.suspensionReason.set(reason_hash);
+
+        log(
+            self.vm(),
+            PageSuspended {
+                owner,
+                moderator: sender,
+                handle,
+                reasonHash: reason_hash,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Lift a page suspension (moderators only)
+    pub fn unsuspend_page(&mut self, handle: String) -> Result<(), OnClickContractError> {
+        let sender = self.vm().msg_sender();
+        if !self.is_moderator(sender) {
+            return Err(OnClickContractError::NotModerator(NotModerator {}));
+        }
+
+        let owner = self.handleToAddress.get(handle.clone());
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        let mut page = self.pages.setter(owner);
+        page.isSuspended.set(false);
+        page.suspensionReason.set(FixedBytes::ZERO);
+
+        log(
+            self.vm(),
+            PageUnsuspended {
+                owner,
+                moderator: sender,
+                handle,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Grant or revoke moderator rights (admin only)
+    pub fn set_moderator(
+        &mut self,
+        moderator: Address,
+        enabled: bool,
+    ) -> Result<(), OnClickContractError> {
+        let sender = self.vm().msg_sender();
+        if sender != self.owner.get() {
+            return Err(OnClickContractError::NotPageOwner(NotPageOwner {}));
+        }
+
+        self.moderators.setter(moderator).set(enabled);
+
+        log(self.vm(), ModeratorUpdated { moderator, enabled });
+
+        Ok(())
+    }
+
+    /// Check if an address can moderate pages (the platform owner always can)
+    pub fn is_moderator(&self, account: Address) -> bool {
+        account == self.owner.get() || self.moderators.get(account)
+    }
+
+    // ==================== Payment Functions ====================
+
+    /// Make a donation to a Creator. An optional `integrator` (e.g. a partner checkout)
+    /// receives `integrator_fee_bps` of the amount, capped by the platform maximum.
+    /// `message` and `display_name` are stored on-chain; `anonymous` hides the donor.
+    #[payable]
+    #[allow(clippy::too_many_arguments)]
+    pub fn make_donation(
+        &mut self,
+        handle: String,
+        message_hash: String,
+        message: String,
+        display_name: String,
+        anonymous: bool,
+        integrator: Address,
+        integrator_fee_bps: U256,
+    ) -> Result<U256, OnClickContractError> {
+        let amount = self.vm().msg_value();
+        if amount == U256::ZERO {
+            return Err(OnClickContractError::InvalidAmount(InvalidAmount {}));
+        }
+
+        check_message(&message, &display_name)?;
+
+        let sender = self.vm().msg_sender();
+        let page_addr = self.handleToAddress.getter(handle.clone());
+        let recipient = page_addr.get();
+
+        if recipient == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        let page = self.pages.getter(recipient);
+        let page_id = page.id.get();
+
+        if !page.isActive.get() {
+            return Err(OnClickContractError::PageNotActive(PageNotActive {}));
+        }
+
+        if page.isSuspended.get() {
+            return Err(OnClickContractError::PageUnderSuspension(
+                PageUnderSuspension {},
+            ));
+        }
+
+        if !has_capability(&page, CAPABILITY_TIPS) {
+            return Err(OnClickContractError::CapabilityNotEnabled(
+                CapabilityNotEnabled {},
+            ));
+        }
+
+        // Take platform and integrator fees
+        let net_amount = self.take_fees(amount, recipient, integrator, integrator_fee_bps)?;
+
+        self.record_supporter(page_id, recipient, sender, net_amount, anonymous);
+
+        // Transfer funds
+        self.distribute_revenue(page_id, recipient, net_amount)?;
 
         // Create transaction
         let tx_id = self.transactionId.get();
         self.transactionId.set(tx_id + U256::from(1));
         let timestamp = U256::from(self.vm().block_timestamp());
 
+        self.pageTransactions.setter(page_id).push(tx_id);
+        self.record_page_stats(page_id, net_amount, false);
+        self.record_platform_volume(recipient, amount);
+
         let mut transaction = self.transactions.setter(tx_id);
         transaction.id.set(tx_id);
         transaction.from.set(sender);
@@ -480,24 +1447,45 @@ impl OnClickContract {
         transaction.productId.set(U256::ZERO);
         transaction.timestamp.set(timestamp);
         transaction.isRefunded.set(false);
+        transaction.tip.set(U256::ZERO);
+        transaction.orderReference.set(FixedBytes::ZERO);
+        transaction.message.set_str(&message);
+        transaction.displayName.set_str(&display_name);
+        transaction.isAnonymous.set(anonymous);
 
         // Emit event
-        evm::log(DonationMade {
-            from: sender,
-            to: recipient,
-            amount: net_amount,
-            messageHash: message_hash,
-        });
+        let from = if anonymous { Address::ZERO } else { sender };
+        log(
+            self.vm(),
+            DonationMade {
+                from,
+                to: recipient,
+                amount: net_amount,
+                messageHash: message_hash,
+                message,
+                displayName: display_name,
+            },
+        );
 
         Ok(tx_id)
     }
 
-    /// Purchase a product from a Business
+    /// Purchase a product from a Business, optionally adding a `tip` on top of
+    /// the price. Any value beyond price and tip is handled by the page's
+    /// overpayment policy. A non-zero `referrer` receives the product's
+    /// affiliate commission out of the net price. A non-zero `order_reference`
+    /// can only be used once per page.
     #[payable]
+    #[allow(clippy::too_many_arguments)]
     pub fn purchase_product(
         &mut self,
         handle: String,
         product_id: U256,
+        referrer: Address,
+        order_reference: FixedBytes<32>,
+        tip: U256,
+        integrator: Address,
+        integrator_fee_bps: U256,
     ) -> Result<U256, OnClickContractError> {
         let amount = self.vm().msg_value();
         if amount == U256::ZERO {
@@ -512,18 +1500,36 @@ impl OnClickContract {
             return Err(OnClickContractError::PageNotFound(PageNotFound {}));
         }
 
+        if referrer == sender || referrer == business {
+            return Err(OnClickContractError::InvalidAddress(InvalidAddress {}));
+        }
+
         let page = self.pages.getter(business);
-        if page.role.get() != U8::from(1) {
-            // 1 = Business
-            return Err(OnClickContractError::InvalidRole(InvalidRole {}));
+        if !page.isActive.get() {
+            return Err(OnClickContractError::PageNotActive(PageNotActive {}));
+        }
+
+        if page.isSuspended.get() {
+            return Err(OnClickContractError::PageUnderSuspension(
+                PageUnderSuspension {},
+            ));
         }
 
+        if !has_capability(&page, CAPABILITY_PRODUCTS) {
+            return Err(OnClickContractError::CapabilityNotEnabled(
+                CapabilityNotEnabled {},
+            ));
+        }
+        let page_id = page.id.get();
+        let store_affiliate_bps = page.affiliateBps.get();
+        let overpayment_policy = page.overpaymentPolicy.get().to::<u8>();
+
         let mut product = self.products.setter(product_id);
         if product.businessOwner.get() == Address::ZERO {
             return Err(OnClickContractError::ProductNotFound(ProductNotFound {}));
         }
 
-        if product.businessOwner.get() != business {
+        if product.pageId.get() != page_id {
             return Err(OnClickContractError::ProductNotFound(ProductNotFound {}));
         }
 
@@ -532,30 +1538,82 @@ impl OnClickContract {
         }
 
         let price = product.price.get();
-        if amount != price {
+        let charged = price
+            .checked_add(tip)
+            .ok_or(OnClickContractError::InvalidAmount(InvalidAmount {}))?;
+        if charged == U256::ZERO || amount < charged {
             return Err(OnClickContractError::InvalidAmount(InvalidAmount {}));
         }
 
-        // Calculate platform fee
-        let fee = (amount * self.platformFee.get()) / U256::from(10000);
-        let net_amount = amount - fee;
+        // Apply the overpayment policy to anything beyond price and tip
+        let mut tip = tip;
+        let mut change = amount - charged;
+        if change != U256::ZERO {
+            if overpayment_policy == OverpaymentPolicy::Tip as u8 {
+                tip += change;
+                change = U256::ZERO;
+            } else if overpayment_policy != OverpaymentPolicy::Refund as u8 {
+                return Err(OnClickContractError::InvalidAmount(InvalidAmount {}));
+            }
+        }
+        let paid = amount - change;
 
         // Update product sales
         let sold = product.totalSold.get();
         product.totalSold.set(sold + U256::from(1));
 
-        // Transfer funds
-        self.vm().transfer_eth(business, net_amount)?;
+        // Product rate, falling back to the storewide rate
+        let mut affiliate_bps = product.affiliateBps.get();
+        if affiliate_bps == U256::ZERO {
+            affiliate_bps = store_affiliate_bps;
+        }
 
-        // Update platform fees
-        let fees = self.platformFeesCollected.get();
-        self.platformFeesCollected.set(fees + fee);
+        // Take platform and integrator fees
+        let net_amount = self.take_fees(paid, business, integrator, integrator_fee_bps)?;
+
+        // Pay affiliate commission out of the net price, excluding the tip
+        let mut commission = U256::ZERO;
+        if referrer != Address::ZERO && affiliate_bps != U256::ZERO {
+            commission = net_amount
+                .checked_mul(price)
+                .map(|amount| amount / paid)
+                .and_then(|net_price| net_price.checked_mul(affiliate_bps))
+                .map(|amount| amount / U256::from(10000))
+                .ok_or(OnClickContractError::InvalidAmount(InvalidAmount {}))?;
+            self.credit_balance(referrer, commission);
+
+            let earned = self.affiliateEarnings.getter(referrer).get(page_id);
+            self.affiliateEarnings
+                .setter(referrer)
+                .setter(page_id)
+                .set(earned + commission);
+
+            log(
+                self.vm(),
+                AffiliatePaid {
+                    affiliate: referrer,
+                    business,
+                    productId: product_id,
+                    amount: commission,
+                },
+            );
+        }
+        let net_amount = net_amount
+            .checked_sub(commission)
+            .ok_or(OnClickContractError::InvalidAmount(InvalidAmount {}))?;
+
+        // Transfer funds
+        self.distribute_revenue(page_id, business, net_amount)?;
 
         // Create transaction
         let tx_id = self.transactionId.get();
         self.transactionId.set(tx_id + U256::from(1));
         let timestamp = U256::from(self.vm().block_timestamp());
 
+        self.pageTransactions.setter(page_id).push(tx_id);
+        self.record_page_stats(page_id, net_amount, true);
+        self.record_platform_volume(business, paid);
+
         let mut transaction = self.transactions.setter(tx_id);
         transaction.id.set(tx_id);
         transaction.from.set(sender);
@@ -566,30 +1624,54 @@ impl OnClickContract {
         transaction.productId.set(product_id);
         transaction.timestamp.set(timestamp);
         transaction.isRefunded.set(false);
+        transaction.tip.set(tip);
+        transaction.orderReference.set(order_reference);
+        transaction.message.set_str("");
+        transaction.displayName.set_str("");
+        transaction.isAnonymous.set(false);
+        self.use_order_reference(page_id, order_reference, tx_id)?;
 
         // Emit event
-        evm::log(ProductPurchased {
-            buyer: sender,
-            seller: business,
-            productId: product_id,
-            amount: net_amount,
-        });
+        log(
+            self.vm(),
+            ProductPurchased {
+                buyer: sender,
+                seller: business,
+                productId: product_id,
+                amount: net_amount,
+                tip,
+                orderReference: order_reference,
+            },
+        );
+
+        // Return any change
+        if change != U256::ZERO {
+            self.vm().transfer_eth(sender, change)?;
+        }
 
         Ok(tx_id)
     }
 
-    /// Contribute to a Crowdfunder campaign
+    /// Contribute to a Crowdfunder campaign, with an optional on-chain message
     #[payable]
+    #[allow(clippy::too_many_arguments)]
     pub fn contribute_to_campaign(
         &mut self,
         handle: String,
         message_hash: String,
+        message: String,
+        display_name: String,
+        anonymous: bool,
+        integrator: Address,
+        integrator_fee_bps: U256,
     ) -> Result<U256, OnClickContractError> {
         let amount = self.vm().msg_value();
         if amount == U256::ZERO {
             return Err(OnClickContractError::InvalidAmount(InvalidAmount {}));
         }
 
+        check_message(&message, &display_name)?;
+
         let sender = self.vm().msg_sender();
         let page_addr = self.handleToAddress.getter(handle.clone());
         let recipient = page_addr.get();
@@ -598,7 +1680,8 @@ impl OnClickContract {
             return Err(OnClickContractError::PageNotFound(PageNotFound {}));
         }
 
-        let mut page = self.pages.setter(recipient);
+        let page = self.pages.getter(recipient);
+        let page_id = page.id.get();
 
         if !page.isActive.get() {
             return Err(OnClickContractError::CampaignNotActive(
@@ -606,35 +1689,37 @@ impl OnClickContract {
             ));
         }
 
-        if page.role.get() != U8::from(2) {
-            // 2 = Crowdfunder
-            return Err(OnClickContractError::InvalidRole(InvalidRole {}));
+        if page.isSuspended.get() {
+            return Err(OnClickContractError::PageUnderSuspension(
+                PageUnderSuspension {},
+            ));
         }
 
-        // Calculate platform fee
-        let fee = (amount * self.platformFee.get()) / U256::from(10000);
-        let net_amount = amount - fee;
+        if !has_capability(&page, CAPABILITY_CAMPAIGNS) {
+            return Err(OnClickContractError::CapabilityNotEnabled(
+                CapabilityNotEnabled {},
+            ));
+        }
 
-        // Update page stats
-        let raised = page.raised.get();
-        page.raised.set(raised + net_amount);
-        let supporters = page.supporters.get();
-        page.supporters.set(supporters + U256::from(1));
+        // Take platform and integrator fees
+        let net_amount = self.take_fees(amount, recipient, integrator, integrator_fee_bps)?;
+
+        self.record_supporter(page_id, recipient, sender, net_amount, anonymous);
 
         // Transfer funds (campaigns hold funds in contract)
         // Note: For campaigns, funds might be held until goal is reached
         // For now, we transfer directly
-        self.vm().transfer_eth(recipient, net_amount)?;
-
-        // Update platform fees
-        let fees = self.platformFeesCollected.get();
-        self.platformFeesCollected.set(fees + fee);
+        self.distribute_revenue(page_id, recipient, net_amount)?;
 
         // Create transaction
         let tx_id = self.transactionId.get();
         self.transactionId.set(tx_id + U256::from(1));
         let timestamp = U256::from(self.vm().block_timestamp());
 
+        self.pageTransactions.setter(page_id).push(tx_id);
+        self.record_page_stats(page_id, net_amount, false);
+        self.record_platform_volume(recipient, amount);
+
         let mut transaction = self.transactions.setter(tx_id);
         transaction.id.set(tx_id);
         transaction.from.set(sender);
@@ -645,218 +1730,997 @@ impl OnClickContract {
         transaction.productId.set(U256::ZERO);
         transaction.timestamp.set(timestamp);
         transaction.isRefunded.set(false);
+        transaction.tip.set(U256::ZERO);
+        transaction.orderReference.set(FixedBytes::ZERO);
+        transaction.message.set_str(&message);
+        transaction.displayName.set_str(&display_name);
+        transaction.isAnonymous.set(anonymous);
 
         // Emit event
-        evm::log(CampaignContribution {
-            from: sender,
-            to: recipient,
-            amount: net_amount,
-            messageHash: message_hash,
-        });
+        let from = if anonymous { Address::ZERO } else { sender };
+        log(
+            self.vm(),
+            CampaignContribution {
+                from,
+                to: recipient,
+                amount: net_amount,
+                messageHash: message_hash,
+                message,
+                displayName: display_name,
+            },
+        );
 
         Ok(tx_id)
     }
 
-    /// Withdraw funds from page
+    /// Withdraw part of the income credited to the page's payout address
+    /// (allowed while the page is inactive or suspended)
     pub fn withdraw_funds(
         &mut self,
         handle: String,
         amount: U256,
     ) -> Result<(), OnClickContractError> {
-        let sender = self.vm().msg_sender();
-        let page_addr = self.handleToAddress.getter(handle.clone());
-        let owner = page_addr.get();
-
-        if owner == Address::ZERO {
-            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
-        }
-
-        if owner != sender {
-            return Err(OnClickContractError::NotPageOwner(NotPageOwner {}));
-        }
+        let owner = self.authorize_page(&handle, OWNER_ONLY)?;
 
-        let page = self.pages.getter(owner);
-        let raised = page.raised.get();
+        let payout = self.payout_address_of(owner);
+        let balance = self.pendingWithdrawals.get(payout);
 
-        if amount > raised {
+        if amount > balance {
             return Err(OnClickContractError::InsufficientFunds(
                 InsufficientFunds {},
             ));
         }
 
-        // Update raised amount
-        let mut page_mut = self.pages.setter(owner);
-        page_mut.raised.set(raised - amount);
+        self.pendingWithdrawals.setter(payout).set(balance - amount);
 
         // Transfer funds
-        self.vm().transfer_eth(sender, amount)?;
+        self.vm().transfer_eth(payout, amount)?;
 
-        evm::log(FundsWithdrawn { owner, amount });
+        log(self.vm(), FundsWithdrawn { owner, amount });
 
         Ok(())
     }
 
-    /// Get page balance
-    pub fn get_page_balance(&self, handle: String) -> Result<U256, OnClickContractError> {
-        let page_addr = self.handleToAddress.getter(handle);
-        let owner = page_addr.get();
+    /// Withdraw the caller's credited balance (page income, revenue splits and other credits)
+    pub fn withdraw_balance(&mut self) -> Result<U256, OnClickContractError> {
+        let sender = self.vm().msg_sender();
+        let amount = self.pendingWithdrawals.get(sender);
+
+        if amount == U256::ZERO {
+            return Err(OnClickContractError::InsufficientFunds(
+                InsufficientFunds {},
+            ));
+        }
+
+        self.pendingWithdrawals.delete(sender);
+
+        self.vm().transfer_eth(sender, amount)?;
+
+        log(
+            self.vm(),
+            BalanceWithdrawn {
+                account: sender,
+                amount,
+            },
+        );
+
+        Ok(amount)
+    }
 
+    /// Get an address's withdrawable balance
+    pub fn get_withdrawable_balance(&self, account: Address) -> U256 {
+        self.pendingWithdrawals.get(account)
+    }
+
+    // ==================== Transactions ====================
+
+    /// Get a transaction by id
+    pub fn get_transaction(&self, tx_id: U256) -> Result<TransactionView, OnClickContractError> {
+        let transaction = self.transactions.getter(tx_id);
+
+        if transaction.from.get() == Address::ZERO {
+            return Err(OnClickContractError::TransactionNotFound(
+                TransactionNotFound {},
+            ));
+        }
+
+        Ok(transaction_view(&transaction))
+    }
+
+    /// Get the transaction a page recorded under a merchant order reference
+    pub fn get_transaction_by_reference(
+        &self,
+        handle: String,
+        order_reference: FixedBytes<32>,
+    ) -> Result<TransactionView, OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
         if owner == Address::ZERO {
             return Err(OnClickContractError::PageNotFound(PageNotFound {}));
         }
 
-        let page = self.pages.getter(owner);
-        Ok(page.raised.get())
+        let page_id = self.pages.getter(owner).id.get();
+        let stored = self
+            .pageOrderReferences
+            .getter(page_id)
+            .get(order_reference);
+        if order_reference == FixedBytes::ZERO || stored == U256::ZERO {
+            return Err(OnClickContractError::TransactionNotFound(
+                TransactionNotFound {},
+            ));
+        }
+
+        self.get_transaction(stored - U256::from(1))
     }
 
-    // ==================== Product Management ====================
+    /// Get a page's transactions newest first
+    pub fn get_recent_transactions(
+        &self,
+        handle: String,
+        offset: U256,
+        limit: U256,
+    ) -> Result<Vec<TransactionView>, OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
 
-    /// Create a new product
-    pub fn create_product(
-        &mut self,
+        let page_id = self.pages.getter(owner).id.get();
+        let tx_ids = self.pageTransactions.getter(page_id);
+        let offset = offset.saturating_to::<usize>();
+        let limit = limit.saturating_to::<usize>().min(MAX_PAGE_SIZE);
+        let mut result = Vec::new();
+
+        for i in (0..tx_ids.len()).rev().skip(offset).take(limit) {
+            let transaction = self.transactions.getter(tx_ids.get(i).unwrap());
+            result.push(transaction_view(&transaction));
+        }
+
+        Ok(result)
+    }
+
+    // ==================== Supporters ====================
+
+    /// Get a supporter's total net amount paid to a page and their number of payments
+    pub fn get_supporter_total(
+        &self,
         handle: String,
-        name: String,
-        price: U256,
-        metadata_hash: String,
-    ) -> Result<U256, OnClickContractError> {
-        let sender = self.vm().msg_sender();
-        let page_addr = self.handleToAddress.getter(handle.clone());
-        let owner = page_addr.get();
+        supporter: Address,
+    ) -> Result<(U256, U256), OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        let page_id = self.pages.getter(owner).id.get();
+        let records = self.supporterRecords.getter(page_id);
+        let record = records.getter(supporter);
+
+        Ok((record.total.get(), record.payments.get()))
+    }
 
+    /// Get a page's supporters in order of their first payment. Each entry is the
+    /// supporter (zero if they only paid anonymously), total paid and payment count.
+    pub fn get_supporters(
+        &self,
+        handle: String,
+        offset: U256,
+        limit: U256,
+    ) -> Result<Vec<(Address, U256, U256)>, OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
         if owner == Address::ZERO {
             return Err(OnClickContractError::PageNotFound(PageNotFound {}));
         }
 
-        if owner != sender {
-            return Err(OnClickContractError::NotPageOwner(NotPageOwner {}));
+        let page_id = self.pages.getter(owner).id.get();
+        let supporters = self.pageSupporters.getter(page_id);
+        let records = self.supporterRecords.getter(page_id);
+        let offset = offset.saturating_to::<usize>();
+        let limit = limit.saturating_to::<usize>().min(MAX_PAGE_SIZE);
+        let mut result = Vec::new();
+
+        for i in (0..supporters.len()).skip(offset).take(limit) {
+            let supporter = supporters.get(i).unwrap();
+            let record = records.getter(supporter);
+            let visible = if record.isPublic.get() {
+                supporter
+            } else {
+                Address::ZERO
+            };
+            result.push((visible, record.total.get(), record.payments.get()));
+        }
+
+        Ok(result)
+    }
+
+    /// Get a page's top supporters by total paid, highest first
+    pub fn get_leaderboard(
+        &self,
+        handle: String,
+    ) -> Result<Vec<(Address, U256)>, OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        let page_id = self.pages.getter(owner).id.get();
+        let board = self.leaderboards.getter(page_id);
+        let records = self.supporterRecords.getter(page_id);
+        let mut result = Vec::new();
+
+        for i in 0..board.len() {
+            let supporter = board.get(i).unwrap();
+            result.push((supporter, records.getter(supporter).total.get()));
+        }
+
+        Ok(result)
+    }
+
+    // ==================== Statistics ====================
+
+    /// Get a page's lifetime total raised, unique supporters and transaction count
+    pub fn get_page_stats(
+        &self,
+        handle: String,
+    ) -> Result<(U256, U256, U256), OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
         }
 
         let page = self.pages.getter(owner);
-        if page.role.get() != U8::from(1) {
-            // 1 = Business
-            return Err(OnClickContractError::InvalidRole(InvalidRole {}));
+        let stats = self.pageStats.getter(page.id.get());
+
+        Ok((
+            stats.totalRaised.get(),
+            page.supporters.get(),
+            stats.transactionCount.get(),
+        ))
+    }
+
+    /// Get a business's total revenue from product sales and invoices, order count
+    /// (purchases and invoice payments) and active product count
+    pub fn get_business_stats(
+        &self,
+        handle: String,
+    ) -> Result<(U256, U256, U256), OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        let page_id = self.pages.getter(owner).id.get();
+        let stats = self.pageStats.getter(page_id);
+
+        Ok((
+            stats.totalRevenue.get(),
+            stats.totalOrders.get(),
+            stats.activeProducts.get(),
+        ))
+    }
+
+    /// Get a campaign's total raised, goal and supporters
+    pub fn get_campaign_stats(
+        &self,
+        handle: String,
+    ) -> Result<(U256, U256, U256), OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        let page = self.pages.getter(owner);
+        let stats = self.pageStats.getter(page.id.get());
+
+        Ok((
+            stats.totalRaised.get(),
+            page.goal.get(),
+            page.supporters.get(),
+        ))
+    }
+
+    /// Get a campaign's total raised, goal, percentage of the goal reached and
+    /// seconds left until its end time (0 once passed or when there is none)
+    pub fn get_campaign_progress(
+        &self,
+        handle: String,
+    ) -> Result<(U256, U256, U256, U256), OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        let page = self.pages.getter(owner);
+        let raised = self.pageStats.getter(page.id.get()).totalRaised.get();
+        let goal = page.goal.get();
+
+        let mut percentage = U256::ZERO;
+        if goal != U256::ZERO {
+            percentage = raised * U256::from(100) / goal;
         }
 
-        let product_id = self.productId.get();
-        self.productId.set(product_id + U256::from(1));
         let timestamp = U256::from(self.vm().block_timestamp());
+        let time_left = page.campaignDeadline.get().saturating_sub(timestamp);
 
-        let mut product = self.products.setter(product_id);
-        product.id.set(product_id);
-        product.businessOwner.set(owner);
-        product.name.set_str(&name);
-        product.price.set(price);
-        product.metadataHash.set_str(&metadata_hash);
-        product.isActive.set(true);
-        product.totalSold.set(U256::ZERO);
-        product.createdAt.set(timestamp);
+        Ok((raised, goal, percentage, time_left))
+    }
 
-        evm::log(ProductCreated {
-            owner,
-            productId: product_id,
-            name: name.clone(),
-            price,
-        });
+    /// Get platform totals: registered creator, business and crowdfunder pages,
+    /// gross volume paid to each role, lifetime platform fees and transaction count
+    pub fn get_platform_stats(&self) -> (U256, U256, U256, U256, U256, U256, U256, U256) {
+        let creator = U8::from(UserRole::Creator as u8);
+        let business = U8::from(UserRole::Business as u8);
+        let crowdfunder = U8::from(UserRole::Crowdfunder as u8);
 
-        Ok(product_id)
+        (
+            self.pagesByRole.get(creator),
+            self.pagesByRole.get(business),
+            self.pagesByRole.get(crowdfunder),
+            self.volumeByRole.get(creator),
+            self.volumeByRole.get(business),
+            self.volumeByRole.get(crowdfunder),
+            self.totalFeesCollected.get(),
+            self.transactionId.get(),
+        )
     }
 
-    /// Update product information
-    pub fn update_product(
+    /// Get the gross volume of each day from `from_day` to `to_day` inclusive,
+    /// where a day is `block_timestamp / 86400`
+    pub fn get_daily_volume(
+        &self,
+        from_day: U256,
+        to_day: U256,
+    ) -> Result<Vec<U256>, OnClickContractError> {
+        if to_day < from_day || to_day - from_day >= U256::from(MAX_DAY_RANGE) {
+            return Err(OnClickContractError::InvalidRange(InvalidRange {}));
+        }
+
+        let mut result = Vec::new();
+        for i in 0..=(to_day - from_day).to::<u64>() {
+            result.push(self.dailyVolume.get(from_day + U256::from(i)));
+        }
+
+        Ok(result)
+    }
+
+    /// Get a page's net revenue and payment count for each day from `from_day`
+    /// to `to_day` inclusive, where a day is `block_timestamp / 86400`
+    pub fn get_page_daily_revenue(
+        &self,
+        handle: String,
+        from_day: U256,
+        to_day: U256,
+    ) -> Result<Vec<(U256, U256)>, OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        if to_day < from_day || to_day - from_day >= U256::from(MAX_DAY_RANGE) {
+            return Err(OnClickContractError::InvalidRange(InvalidRange {}));
+        }
+
+        let page_id = self.pages.getter(owner).id.get();
+        let daily_revenue = self.pageDailyRevenue.getter(page_id);
+        let daily_payments = self.pageDailyPayments.getter(page_id);
+        let mut result = Vec::new();
+        for i in 0..=(to_day - from_day).to::<u64>() {
+            let day = from_day + U256::from(i);
+            result.push((daily_revenue.get(day), daily_payments.get(day)));
+        }
+
+        Ok(result)
+    }
+
+    // ==================== Payout Address ====================
+
+    /// Set where a page's income is paid (zero resets to the owner).
+    /// If the page has a payout timelock, the change is scheduled and must be applied
+    /// with `apply_payout_address` once the timelock has passed.
+    pub fn set_payout_address(
         &mut self,
         handle: String,
-        product_id: U256,
-        name: String,
-        price: U256,
-        metadata_hash: String,
+        payout_address: Address,
     ) -> Result<(), OnClickContractError> {
-        let sender = self.vm().msg_sender();
-        let page_addr = self.handleToAddress.getter(handle.clone());
-        let owner = page_addr.get();
+        let owner = self.authorize_page(&handle, OWNER_ONLY)?;
+        let timestamp = U256::from(self.vm().block_timestamp());
 
-        if owner == Address::ZERO {
-            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        let mut page = self.pages.setter(owner);
+        let timelock = page.payoutTimelock.get();
+
+        if timelock == U256::ZERO {
+            page.payoutAddress.set(payout_address);
+            page.pendingPayoutAddress.set(Address::ZERO);
+            page.payoutChangeAvailableAt.set(U256::ZERO);
+
+            log(
+                self.vm(),
+                PayoutAddressChanged {
+                    owner,
+                    handle,
+                    payoutAddress: payout_address,
+                },
+            );
+        } else {
+            let available_at = timestamp + timelock;
+            page.pendingPayoutAddress.set(payout_address);
+            page.payoutChangeAvailableAt.set(available_at);
+
+            log(
+                self.vm(),
+                PayoutAddressChangeScheduled {
+                    owner,
+                    handle,
+                    payoutAddress: payout_address,
+                    availableAt: available_at,
+                },
+            );
         }
 
-        if owner != sender {
-            return Err(OnClickContractError::NotPageOwner(NotPageOwner {}));
+        Ok(())
+    }
+
+    /// Apply a scheduled payout address change after its timelock
+    pub fn apply_payout_address(&mut self, handle: String) -> Result<(), OnClickContractError> {
+        let owner = self.authorize_page(&handle, OWNER_ONLY)?;
+        let timestamp = U256::from(self.vm().block_timestamp());
+
+        let mut page = self.pages.setter(owner);
+        let available_at = page.payoutChangeAvailableAt.get();
+
+        if available_at == U256::ZERO {
+            return Err(OnClickContractError::NoPendingPayoutChange(
+                NoPendingPayoutChange {},
+            ));
         }
 
-        let mut product = self.products.setter(product_id);
-        if product.businessOwner.get() != owner {
-            return Err(OnClickContractError::ProductNotFound(ProductNotFound {}));
+        if timestamp < available_at {
+            return Err(OnClickContractError::PayoutTimelockActive(
+                PayoutTimelockActive {},
+            ));
         }
 
-        product.name.set_str(&name);
-        product.price.set(price);
-        product.metadataHash.set_str(&metadata_hash);
+        let payout_address = page.pendingPayoutAddress.get();
+        page.payoutAddress.set(payout_address);
+        page.pendingPayoutAddress.set(Address::ZERO);
+        page.payoutChangeAvailableAt.set(U256::ZERO);
 
-        evm::log(ProductUpdated {
-            owner,
-            productId: product_id,
-            metadataHash: metadata_hash,
-        });
+        log(
+            self.vm(),
+            PayoutAddressChanged {
+                owner,
+                handle,
+                payoutAddress: payout_address,
+            },
+        );
 
         Ok(())
     }
 
-    /// Delete/deactivate a product
-    pub fn delete_product(
+    /// Set the delay applied to payout address and revenue split changes. The
+    /// timelock can only be raised, so a compromised key cannot remove it to
+    /// redirect funds immediately.
+    pub fn set_payout_timelock(
         &mut self,
         handle: String,
-        product_id: U256,
+        timelock: U256,
     ) -> Result<(), OnClickContractError> {
-        let sender = self.vm().msg_sender();
-        let page_addr = self.handleToAddress.getter(handle.clone());
-        let owner = page_addr.get();
+        let owner = self.authorize_page(&handle, OWNER_ONLY)?;
+
+        let mut page = self.pages.setter(owner);
+        if timelock < page.payoutTimelock.get() || timelock > U256::from(MAX_PAYOUT_TIMELOCK) {
+            return Err(OnClickContractError::InvalidTimelock(InvalidTimelock {}));
+        }
+
+        page.payoutTimelock.set(timelock);
+
+        log(
+            self.vm(),
+            PayoutTimelockUpdated {
+                owner,
+                handle,
+                timelock,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Get payout settings (payout address, pending address, pending available at, timelock)
+    pub fn get_payout_info(
+        &self,
+        handle: String,
+    ) -> Result<(Address, Address, U256, U256), OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
 
         if owner == Address::ZERO {
             return Err(OnClickContractError::PageNotFound(PageNotFound {}));
         }
 
-        if owner != sender {
-            return Err(OnClickContractError::NotPageOwner(NotPageOwner {}));
+        let page = self.pages.getter(owner);
+        Ok((
+            self.payout_address_of(owner),
+            page.pendingPayoutAddress.get(),
+            page.payoutChangeAvailableAt.get(),
+            page.payoutTimelock.get(),
+        ))
+    }
+
+    // ==================== Revenue Splits ====================
+
+    /// Set how a page's income is split. Each entry pays either an address or,
+    /// if `recipient_handles[i]` is non-empty, the current owner of that handle's
+    /// page (resolved now, so later handle changes do not redirect the share).
+    /// Shares are in basis points and must sum to 10000; empty lists clear the splits.
+    /// If the page has a payout timelock, the change is scheduled and must be applied
+    /// with `apply_revenue_splits` once the timelock has passed.
+    pub fn set_revenue_splits(
+        &mut self,
+        handle: String,
+        recipients: Vec<Address>,
+        recipient_handles: Vec<String>,
+        shares_bps: Vec<U256>,
+    ) -> Result<(), OnClickContractError> {
+        let owner = self.authorize_page(&handle, OWNER_ONLY)?;
+
+        let count = recipients.len();
+        if count != recipient_handles.len()
+            || count != shares_bps.len()
+            || count > MAX_SPLIT_RECIPIENTS
+        {
+            return Err(OnClickContractError::InvalidSplits(InvalidSplits {}));
         }
 
-        let mut product = self.products.setter(product_id);
-        if product.businessOwner.get() != owner {
-            return Err(OnClickContractError::ProductNotFound(ProductNotFound {}));
+        let mut total = U256::ZERO;
+        let mut entries = Vec::new();
+        for i in 0..count {
+            let by_handle = !recipient_handles[i].is_empty();
+            if by_handle == (recipients[i] != Address::ZERO) || shares_bps[i] == U256::ZERO {
+                return Err(OnClickContractError::InvalidSplits(InvalidSplits {}));
+            }
+
+            let mut recipient_page = None;
+            if by_handle {
+                let handle_owner = self.handleToAddress.get(recipient_handles[i].clone());
+                if handle_owner == Address::ZERO {
+                    return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+                }
+                recipient_page = Some(self.pages.getter(handle_owner).id.get());
+            }
+            entries.push((recipients[i], recipient_page, shares_bps[i]));
+
+            total += shares_bps[i];
         }
 
-        product.isActive.set(false);
+        if count > 0 && total != U256::from(10000) {
+            return Err(OnClickContractError::InvalidSplits(InvalidSplits {}));
+        }
 
-        evm::log(ProductDeleted {
-            owner,
-            productId: product_id,
-        });
+        let page = self.pages.getter(owner);
+        let page_id = page.id.get();
+        let timelock = page.payoutTimelock.get();
+
+        if timelock == U256::ZERO {
+            store_splits(&mut self.pageSplits.setter(page_id), &entries);
+            self.pendingPageSplits.setter(page_id).erase();
+            self.splitsChangeAvailableAt.delete(page_id);
+
+            log(
+                self.vm(),
+                RevenueSplitsUpdated {
+                    owner,
+                    handle,
+                    recipientCount: U256::from(count),
+                },
+            );
+        } else {
+            let available_at = U256::from(self.vm().block_timestamp()) + timelock;
+            store_splits(&mut self.pendingPageSplits.setter(page_id), &entries);
+            self.splitsChangeAvailableAt
+                .setter(page_id)
+                .set(available_at);
+
+            log(
+                self.vm(),
+                RevenueSplitsChangeScheduled {
+                    owner,
+                    handle,
+                    recipientCount: U256::from(count),
+                    availableAt: available_at,
+                },
+            );
+        }
 
         Ok(())
     }
 
-    /// Get product by ID
-    pub fn get_product(
+    /// Apply a scheduled revenue split change after its timelock
+    pub fn apply_revenue_splits(&mut self, handle: String) -> Result<(), OnClickContractError> {
+        let owner = self.authorize_page(&handle, OWNER_ONLY)?;
+        let timestamp = U256::from(self.vm().block_timestamp());
+
+        let page_id = self.pages.getter(owner).id.get();
+        let available_at = self.splitsChangeAvailableAt.get(page_id);
+
+        if available_at == U256::ZERO {
+            return Err(OnClickContractError::NoPendingPayoutChange(
+                NoPendingPayoutChange {},
+            ));
+        }
+
+        if timestamp < available_at {
+            return Err(OnClickContractError::PayoutTimelockActive(
+                PayoutTimelockActive {},
+            ));
+        }
+
+        let pending = self.pendingPageSplits.getter(page_id);
+        let mut entries = Vec::new();
+        for i in 0..pending.len() {
+            let split = pending.getter(i).unwrap();
+            let recipient_page = split.isPage.get().then(|| split.pageId.get());
+            entries.push((split.recipient.get(), recipient_page, split.bps.get()));
+        }
+
+        store_splits(&mut self.pageSplits.setter(page_id), &entries);
+        self.pendingPageSplits.setter(page_id).erase();
+        self.splitsChangeAvailableAt.delete(page_id);
+
+        log(
+            self.vm(),
+            RevenueSplitsUpdated {
+                owner,
+                handle,
+                recipientCount: U256::from(entries.len()),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Get a page's scheduled revenue splits and when they can be applied (0 = none)
+    pub fn get_pending_revenue_splits(
         &self,
-        product_id: U256,
-    ) -> Result<(U256, Address, String, U256, String, bool, U256, U256), OnClickContractError> {
-        let product = self.products.getter(product_id);
+        handle: String,
+    ) -> Result<(Vec<(Address, String, U256)>, U256), OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
 
-        if product.businessOwner.get() == Address::ZERO {
-            return Err(OnClickContractError::ProductNotFound(ProductNotFound {}));
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
         }
 
-        Ok((
-            product.id.get(),
-            product.businessOwner.get(),
-            product.name.get_string(),
-            product.price.get(),
-            product.metadataHash.get_string(),
-            product.isActive.get(),
-            product.totalSold.get(),
-            product.createdAt.get(),
-        ))
+        let page_id = self.pages.getter(owner).id.get();
+        let splits = self.split_views(&self.pendingPageSplits.getter(page_id));
+        Ok((splits, self.splitsChangeAvailableAt.get(page_id)))
     }
 
-    // ==================== Platform Management ====================
+    /// Get a page's revenue splits as (recipient, recipient page's current handle, share in bps)
+    pub fn get_revenue_splits(
+        &self,
+        handle: String,
+    ) -> Result<Vec<(Address, String, U256)>, OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
+
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        let page_id = self.pages.getter(owner).id.get();
+        Ok(self.split_views(&self.pageSplits.getter(page_id)))
+    }
+
+    /// Get page balance: the income credited to the page's payout address
+    pub fn get_page_balance(&self, handle: String) -> Result<U256, OnClickContractError> {
+        let page_addr = self.handleToAddress.getter(handle);
+        let owner = page_addr.get();
+
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        let payout = self.payout_address_of(owner);
+        Ok(self.pendingWithdrawals.get(payout))
+    }
+
+    // ==================== Product Management ====================
+
+    /// Create a new product
+    pub fn create_product(
+        &mut self,
+        handle: String,
+        name: String,
+        price: U256,
+        metadata_hash: String,
+    ) -> Result<U256, OnClickContractError> {
+        let owner = self.authorize_page(&handle, PERMISSION_PRODUCTS)?;
+
+        let page = self.pages.getter(owner);
+        if !has_capability(&page, CAPABILITY_PRODUCTS) {
+            return Err(OnClickContractError::CapabilityNotEnabled(
+                CapabilityNotEnabled {},
+            ));
+        }
+        let page_id = page.id.get();
+
+        let product_id = self.productId.get();
+        self.productId.set(product_id + U256::from(1));
+        let timestamp = U256::from(self.vm().block_timestamp());
+
+        let mut product = self.products.setter(product_id);
+        product.id.set(product_id);
+        product.businessOwner.set(owner);
+        product.name.set_str(&name);
+        product.price.set(price);
+        product.metadataHash.set_str(&metadata_hash);
+        product.isActive.set(true);
+        product.totalSold.set(U256::ZERO);
+        product.createdAt.set(timestamp);
+        product.pageId.set(page_id);
+
+        let mut stats = self.pageStats.setter(page_id);
+        let active_products = stats.activeProducts.get();
+        stats.activeProducts.set(active_products + U256::from(1));
+
+        log(
+            self.vm(),
+            ProductCreated {
+                owner,
+                productId: product_id,
+                name: name.clone(),
+                price,
+            },
+        );
+
+        Ok(product_id)
+    }
+
+    /// Update product information
+    pub fn update_product(
+        &mut self,
+        handle: String,
+        product_id: U256,
+        name: String,
+        price: U256,
+        metadata_hash: String,
+    ) -> Result<(), OnClickContractError> {
+        let owner = self.authorize_page(&handle, PERMISSION_PRODUCTS)?;
+
+        let page_id = self.pages.getter(owner).id.get();
+        let mut product = self.products.setter(product_id);
+        if product.businessOwner.get() == Address::ZERO || product.pageId.get() != page_id {
+            return Err(OnClickContractError::ProductNotFound(ProductNotFound {}));
+        }
+
+        product.name.set_str(&name);
+        product.price.set(price);
+        product.metadataHash.set_str(&metadata_hash);
+
+        log(
+            self.vm(),
+            ProductUpdated {
+                owner,
+                productId: product_id,
+                metadataHash: metadata_hash,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Delete/deactivate a product
+    pub fn delete_product(
+        &mut self,
+        handle: String,
+        product_id: U256,
+    ) -> Result<(), OnClickContractError> {
+        let owner = self.authorize_page(&handle, PERMISSION_PRODUCTS)?;
+
+        let page_id = self.pages.getter(owner).id.get();
+        let mut product = self.products.setter(product_id);
+        if product.businessOwner.get() == Address::ZERO || product.pageId.get() != page_id {
+            return Err(OnClickContractError::ProductNotFound(ProductNotFound {}));
+        }
+
+        if product.isActive.get() {
+            product.isActive.set(false);
+
+            let mut stats = self.pageStats.setter(page_id);
+            let active_products = stats.activeProducts.get();
+            stats.activeProducts.set(active_products - U256::from(1));
+        }
+
+        log(
+            self.vm(),
+            ProductDeleted {
+                owner,
+                productId: product_id,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Get product by ID
+    pub fn get_product(&self, product_id: U256) -> Result<ProductView, OnClickContractError> {
+        let product = self.products.getter(product_id);
+
+        if product.businessOwner.get() == Address::ZERO {
+            return Err(OnClickContractError::ProductNotFound(ProductNotFound {}));
+        }
+
+        Ok(ProductView {
+            id: product.id.get(),
+            businessOwner: self.pageAddresses.get(product.pageId.get()),
+            name: product.name.get_string(),
+            price: product.price.get(),
+            metadataHash: product.metadataHash.get_string(),
+            isActive: product.isActive.get(),
+            totalSold: product.totalSold.get(),
+            createdAt: product.createdAt.get(),
+        })
+    }
+
+    /// Set how purchases paying more than price and tip are handled
+    pub fn set_overpayment_policy(
+        &mut self,
+        handle: String,
+        policy: u8,
+    ) -> Result<(), OnClickContractError> {
+        let owner = self.authorize_page(&handle, PERMISSION_PRODUCTS)?;
+
+        if policy > OverpaymentPolicy::Tip as u8 {
+            return Err(OnClickContractError::InvalidOverpaymentPolicy(
+                InvalidOverpaymentPolicy {},
+            ));
+        }
+
+        self.pages
+            .setter(owner)
+            .overpaymentPolicy
+            .set(U8::from(policy));
+
+        log(self.vm(), OverpaymentPolicyUpdated { owner, policy });
+
+        Ok(())
+    }
+
+    /// Get a page's overpayment policy
+    pub fn get_overpayment_policy(&self, handle: String) -> Result<u8, OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
+
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        Ok(self.pages.getter(owner).overpaymentPolicy.get().to::<u8>())
+    }
+
+    /// Get several products. Each entry is a found flag and the product
+    /// (zeroed when not found).
+    pub fn get_products(&self, product_ids: Vec<U256>) -> Vec<(bool, ProductView)> {
+        let mut result = Vec::new();
+
+        for product_id in product_ids {
+            match self.get_product(product_id) {
+                Ok(product) => result.push((true, product)),
+                Err(_) => result.push((false, ProductView::default())),
+            }
+        }
+
+        result
+    }
+
+    // ==================== Affiliates ====================
+
+    /// Set the storewide affiliate commission in basis points of the net sale.
+    /// Owner only, as commissions move page income to the referrer.
+    pub fn set_store_affiliate_bps(
+        &mut self,
+        handle: String,
+        bps: U256,
+    ) -> Result<(), OnClickContractError> {
+        let owner = self.authorize_page(&handle, OWNER_ONLY)?;
+
+        if bps > U256::from(10000) {
+            return Err(OnClickContractError::InvalidCommission(
+                InvalidCommission {},
+            ));
+        }
+
+        self.pages.setter(owner).affiliateBps.set(bps);
+
+        log(
+            self.vm(),
+            AffiliateCommissionUpdated {
+                owner,
+                productId: U256::ZERO,
+                storewide: true,
+                bps,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Set a product's affiliate commission in basis points (0 = storewide rate).
+    /// Owner only, like the storewide rate.
+    pub fn set_product_affiliate_bps(
+        &mut self,
+        handle: String,
+        product_id: U256,
+        bps: U256,
+    ) -> Result<(), OnClickContractError> {
+        let owner = self.authorize_page(&handle, OWNER_ONLY)?;
+
+        if bps > U256::from(10000) {
+            return Err(OnClickContractError::InvalidCommission(
+                InvalidCommission {},
+            ));
+        }
+
+        let page_id = self.pages.getter(owner).id.get();
+        let mut product = self.products.setter(product_id);
+        if product.businessOwner.get() == Address::ZERO || product.pageId.get() != page_id {
+            return Err(OnClickContractError::ProductNotFound(ProductNotFound {}));
+        }
+
+        product.affiliateBps.set(bps);
+
+        log(
+            self.vm(),
+            AffiliateCommissionUpdated {
+                owner,
+                productId: product_id,
+                storewide: false,
+                bps,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Get the affiliate commission (bps) applied to a product's sales
+    pub fn get_affiliate_commission(
+        &self,
+        handle: String,
+        product_id: U256,
+    ) -> Result<U256, OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
+
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        let page = self.pages.getter(owner);
+        let product = self.products.getter(product_id);
+        if product.businessOwner.get() == Address::ZERO || product.pageId.get() != page.id.get() {
+            return Err(OnClickContractError::ProductNotFound(ProductNotFound {}));
+        }
+
+        let bps = product.affiliateBps.get();
+        if bps == U256::ZERO {
+            return Ok(page.affiliateBps.get());
+        }
+
+        Ok(bps)
+    }
+
+    /// Get an affiliate's total commission earned from a business
+    pub fn get_affiliate_earnings(
+        &self,
+        affiliate: Address,
+        handle: String,
+    ) -> Result<U256, OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
+
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        let page_id = self.pages.getter(owner).id.get();
+        Ok(self.affiliateEarnings.getter(affiliate).get(page_id))
+    }
+
+    // ==================== Platform Management ====================
 
     /// Set platform fee (admin only)
     pub fn set_platform_fee(&mut self, fee_percentage: U256) -> Result<(), OnClickContractError> {
@@ -865,57 +2729,1260 @@ impl OnClickContract {
             return Err(OnClickContractError::NotPageOwner(NotPageOwner {}));
         }
 
-        self.platformFee.set(fee_percentage);
+        self.platformFee.set(fee_percentage);
+
+        log(
+            self.vm(),
+            PlatformFeeUpdated {
+                newFee: fee_percentage,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Set the maximum fee integrators can charge, in basis points (admin only)
+    pub fn set_max_integrator_fee(&mut self, max_fee: U256) -> Result<(), OnClickContractError> {
+        let sender = self.vm().msg_sender();
+        if sender != self.owner.get() {
+            return Err(OnClickContractError::NotPageOwner(NotPageOwner {}));
+        }
+
+        if max_fee > U256::from(10000) {
+            return Err(OnClickContractError::IntegratorFeeTooHigh(
+                IntegratorFeeTooHigh {},
+            ));
+        }
+
+        self.maxIntegratorFee.set(max_fee);
+
+        log(self.vm(), MaxIntegratorFeeUpdated { newMaxFee: max_fee });
+
+        Ok(())
+    }
+
+    /// Get the maximum integrator fee in basis points
+    pub fn get_max_integrator_fee(&self) -> U256 {
+        self.maxIntegratorFee.get()
+    }
+
+    /// Withdraw platform fees (admin only)
+    pub fn withdraw_platform_fees(&mut self) -> Result<(), OnClickContractError> {
+        let sender = self.vm().msg_sender();
+        if sender != self.owner.get() {
+            return Err(OnClickContractError::NotPageOwner(NotPageOwner {}));
+        }
+
+        let fees = self.platformFeesCollected.get();
+        self.platformFeesCollected.set(U256::ZERO);
+
+        self.vm().transfer_eth(sender, fees)?;
+
+        log(self.vm(), PlatformFeesWithdrawn { amount: fees });
+
+        Ok(())
+    }
+
+    /// Configure handle commit-reveal timing and whether plain registration is allowed (admin only).
+    /// A zero delay or age falls back to the defaults.
+    pub fn set_handle_commit_config(
+        &mut self,
+        min_delay: U256,
+        max_age: U256,
+        plain_registration_enabled: bool,
+    ) -> Result<(), OnClickContractError> {
+        let sender = self.vm().msg_sender();
+        if sender != self.owner.get() {
+            return Err(OnClickContractError::NotPageOwner(NotPageOwner {}));
+        }
+
+        self.handleCommitMinDelay.set(min_delay);
+        self.handleCommitMaxAge.set(max_age);
+        self.plainRegistrationEnabled
+            .set(plain_registration_enabled);
+
+        let (min_delay, max_age) = self.handle_commit_window();
+        if max_age <= min_delay {
+            return Err(OnClickContractError::InvalidCommitConfig(
+                InvalidCommitConfig {},
+            ));
+        }
+
+        log(
+            self.vm(),
+            HandleCommitConfigUpdated {
+                minDelay: min_delay,
+                maxAge: max_age,
+                plainRegistrationEnabled: plain_registration_enabled,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Get handle commit-reveal settings (min delay, max age, plain registration enabled)
+    pub fn get_handle_commit_config(&self) -> (U256, U256, bool) {
+        let (min_delay, max_age) = self.handle_commit_window();
+        (min_delay, max_age, self.plainRegistrationEnabled.get())
+    }
+
+    /// Get next ID (utility function)
+    pub fn get_next_id(&self) -> U256 {
+        self.pageId.get()
+    }
+
+    // ==================== Payment Intent Functions ====================
+
+    /// Create a payment intent (payment link). A non-zero `quantity` links the intent
+    /// to a product, making it a buy-now link whose amount must be `price * quantity`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_payment_intent(
+        &mut self,
+        handle: String,
+        amount: U256,
+        description: String,
+        expires_in_seconds: U256,
+        max_usages: U256,
+        salt: FixedBytes<32>,
+        product_id: U256,
+        quantity: U256,
+    ) -> Result<FixedBytes<32>, OnClickContractError> {
+        if amount == U256::ZERO {
+            return Err(OnClickContractError::InvalidAmount(InvalidAmount {}));
+        }
+
+        let intent_id = self.create_intent(
+            handle,
+            amount,
+            description,
+            expires_in_seconds,
+            max_usages,
+            salt,
+        )?;
+
+        if quantity != U256::ZERO {
+            let page_id = self.paymentIntents.getter(intent_id).pageId.get();
+            let price = self.linked_product_price(page_id, product_id)?;
+            if price.checked_mul(quantity) != Some(amount) {
+                return Err(OnClickContractError::InvalidAmount(InvalidAmount {}));
+            }
+
+            let mut intent = self.paymentIntents.setter(intent_id);
+            intent.productId.set(product_id);
+            intent.quantity.set(quantity);
+        }
+
+        Ok(intent_id)
+    }
+
+    /// Create an open-amount (pay-what-you-want) payment intent. Payers choose the
+    /// amount within [`min_amount`, `max_amount`] (0 = no maximum); `suggested_amount`
+    /// is a UI hint and `total_cap` limits the total collected across usages (0 = no cap).
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_open_payment_intent(
+        &mut self,
+        handle: String,
+        min_amount: U256,
+        max_amount: U256,
+        suggested_amount: U256,
+        description: String,
+        expires_in_seconds: U256,
+        max_usages: U256,
+        total_cap: U256,
+        salt: FixedBytes<32>,
+    ) -> Result<FixedBytes<32>, OnClickContractError> {
+        if max_amount != U256::ZERO && max_amount < min_amount {
+            return Err(OnClickContractError::InvalidAmount(InvalidAmount {}));
+        }
+
+        if suggested_amount != U256::ZERO
+            && (suggested_amount < min_amount
+                || (max_amount != U256::ZERO && suggested_amount > max_amount))
+        {
+            return Err(OnClickContractError::InvalidAmount(InvalidAmount {}));
+        }
+
+        let intent_id = self.create_intent(
+            handle,
+            U256::ZERO,
+            description,
+            expires_in_seconds,
+            max_usages,
+            salt,
+        )?;
+
+        let mut intent = self.paymentIntents.setter(intent_id);
+        intent.openAmount.set(true);
+        intent.minAmount.set(min_amount);
+        intent.maxAmount.set(max_amount);
+        intent.suggestedAmount.set(suggested_amount);
+        intent.totalCap.set(total_cap);
+
+        Ok(intent_id)
+    }
+
+    /// Get the product a payment intent is linked to (product id, quantity; quantity 0 = none)
+    pub fn get_payment_intent_product(
+        &self,
+        intent_id: FixedBytes<32>,
+    ) -> Result<(U256, U256), OnClickContractError> {
+        let intent = self.paymentIntents.getter(intent_id);
+
+        if intent.creator.get() == Address::ZERO {
+            return Err(OnClickContractError::PaymentIntentNotFound(
+                PaymentIntentNotFound {},
+            ));
+        }
+
+        Ok((intent.productId.get(), intent.quantity.get()))
+    }
+
+    /// Get open-amount settings of a payment intent
+    /// (is open amount, min, max, suggested, total cap, total collected)
+    pub fn get_payment_intent_amounts(
+        &self,
+        intent_id: FixedBytes<32>,
+    ) -> Result<(bool, U256, U256, U256, U256, U256), OnClickContractError> {
+        let intent = self.paymentIntents.getter(intent_id);
+
+        if intent.creator.get() == Address::ZERO {
+            return Err(OnClickContractError::PaymentIntentNotFound(
+                PaymentIntentNotFound {},
+            ));
+        }
+
+        Ok((
+            intent.openAmount.get(),
+            intent.minAmount.get(),
+            intent.maxAmount.get(),
+            intent.suggestedAmount.get(),
+            intent.totalCap.get(),
+            intent.totalCollected.get(),
+        ))
+    }
+
+    /// Compute the id `create_payment_intent` will assign for a page's nonce and salt
+    pub fn compute_intent_id(
+        &self,
+        handle: String,
+        nonce: U256,
+        salt: FixedBytes<32>,
+    ) -> Result<FixedBytes<32>, OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
+
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        let page_id = self.pages.getter(owner).id.get();
+        Ok(payment_intent_id(page_id, nonce, salt))
+    }
+
+    /// Get the nonce the next payment intent of a page will use
+    pub fn get_intent_nonce(&self, handle: String) -> Result<U256, OnClickContractError> {
+        let owner = self.handleToAddress.get(handle);
+
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        let page_id = self.pages.getter(owner).id.get();
+        Ok(self.intentNonces.get(page_id))
+    }
+
+    /// Pay via payment intent. A non-zero `order_reference` can only be used once per page.
+    #[payable]
+    pub fn pay_intent(
+        &mut self,
+        intent_id: FixedBytes<32>,
+        message_hash: String,
+        order_reference: FixedBytes<32>,
+        integrator: Address,
+        integrator_fee_bps: U256,
+    ) -> Result<U256, OnClickContractError> {
+        let amount = self.vm().msg_value();
+        if amount == U256::ZERO {
+            return Err(OnClickContractError::InvalidAmount(InvalidAmount {}));
+        }
+
+        let sender = self.vm().msg_sender();
+        let current_time = U256::from(self.vm().block_timestamp());
+
+        let mut intent = self.paymentIntents.setter(intent_id);
+
+        // Check if intent exists
+        if intent.creator.get() == Address::ZERO {
+            return Err(OnClickContractError::PaymentIntentNotFound(
+                PaymentIntentNotFound {},
+            ));
+        }
+
+        // Check if active
+        if !intent.isActive.get() {
+            return Err(OnClickContractError::PaymentIntentInactive(
+                PaymentIntentInactive {},
+            ));
+        }
+
+        // Check expiration
+        let expires_at = intent.expiresAt.get();
+        if current_time > expires_at {
+            return Err(OnClickContractError::PaymentIntentExpired(
+                PaymentIntentExpired {},
+            ));
+        }
+
+        // Get values before mutation
+        let max_usages = intent.maxUsages.get();
+        let expected_amount = intent.amount.get();
+        let page_id = intent.pageId.get();
+        let product_id = intent.productId.get();
+        let quantity = intent.quantity.get();
+
+        // Verify amount matches, or is within range for open-amount intents
+        if intent.openAmount.get() {
+            let max_amount = intent.maxAmount.get();
+            if amount < intent.minAmount.get() || (max_amount != U256::ZERO && amount > max_amount)
+            {
+                return Err(OnClickContractError::InvalidAmount(InvalidAmount {}));
+            }
+        } else if quantity == U256::ZERO && amount != expected_amount {
+            return Err(OnClickContractError::InvalidAmount(InvalidAmount {}));
+        }
+
+        // Check the cumulative cap
+        let total_cap = intent.totalCap.get();
+        let total_collected = intent.totalCollected.get() + amount;
+        if total_cap != U256::ZERO && total_collected > total_cap {
+            return Err(OnClickContractError::PaymentIntentCapReached(
+                PaymentIntentCapReached {},
+            ));
+        }
+        intent.totalCollected.set(total_collected);
+
+        // Check max usages (usages are counted even when unlimited, so a limit can be added later)
+        let usage_count = intent.usageCount.get();
+        if max_usages != U256::ZERO && usage_count >= max_usages {
+            return Err(OnClickContractError::PaymentIntentMaxUsages(
+                PaymentIntentMaxUsages {},
+            ));
+        }
+        intent.usageCount.set(usage_count + U256::from(1));
+
+        // Get recipient page for role validation
+        let recipient = self.pageAddresses.get(page_id);
+        if recipient == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        let page = self.pages.getter(recipient);
+        if !page.isActive.get() {
+            return Err(OnClickContractError::PageNotActive(PageNotActive {}));
+        }
+
+        if page.isSuspended.get() {
+            return Err(OnClickContractError::PageUnderSuspension(
+                PageUnderSuspension {},
+            ));
+        }
+        let recipient_role = page.role.get();
+
+        // Product-linked intents are charged the product's current price
+        if quantity != U256::ZERO {
+            let price = self.linked_product_price(page_id, product_id)?;
+            if price.checked_mul(quantity) != Some(amount) {
+                return Err(OnClickContractError::InvalidAmount(InvalidAmount {}));
+            }
+
+            let mut product = self.products.setter(product_id);
+            let sold = product.totalSold.get();
+            let sold = sold
+                .checked_add(quantity)
+                .ok_or(OnClickContractError::InvalidAmount(InvalidAmount {}))?;
+            product.totalSold.set(sold);
+        }
+
+        // Take platform and integrator fees
+        let net_amount = self.take_fees(amount, recipient, integrator, integrator_fee_bps)?;
+
+        self.record_supporter(page_id, recipient, sender, net_amount, false);
+
+        // Transfer funds
+        self.distribute_revenue(page_id, recipient, net_amount)?;
+
+        // Create transaction
+        let tx_id = self.transactionId.get();
+        self.transactionId.set(tx_id + U256::from(1));
+        let timestamp = U256::from(self.vm().block_timestamp());
+
+        self.pageTransactions.setter(page_id).push(tx_id);
+        self.record_page_stats(page_id, net_amount, quantity != U256::ZERO);
+        self.record_platform_volume(recipient, amount);
+
+        let mut transaction = self.transactions.setter(tx_id);
+        transaction.id.set(tx_id);
+        transaction.from.set(sender);
+        transaction.to.set(recipient);
+        transaction.amount.set(net_amount);
+        transaction.messageHash.set_str(&message_hash);
+        transaction.recipientRole.set(recipient_role);
+        transaction.productId.set(product_id);
+        transaction.timestamp.set(timestamp);
+        transaction.isRefunded.set(false);
+        transaction.tip.set(U256::ZERO);
+        transaction.orderReference.set(order_reference);
+        transaction.message.set_str("");
+        transaction.displayName.set_str("");
+        transaction.isAnonymous.set(false);
+        self.use_order_reference(page_id, order_reference, tx_id)?;
+
+        // Emit events
+        log(
+            self.vm(),
+            PaymentIntentPaid {
+                intentId: intent_id,
+                payer: sender,
+                amount: net_amount,
+                orderReference: order_reference,
+            },
+        );
+
+        if quantity != U256::ZERO {
+            log(
+                self.vm(),
+                ProductPurchased {
+                    buyer: sender,
+                    seller: recipient,
+                    productId: product_id,
+                    amount: net_amount,
+                    tip: U256::ZERO,
+                    orderReference: order_reference,
+                },
+            );
+        } else {
+            log(
+                self.vm(),
+                DonationMade {
+                    from: sender,
+                    to: recipient,
+                    amount: net_amount,
+                    messageHash: message_hash,
+                    message: String::new(),
+                    displayName: String::new(),
+                },
+            );
+        }
+
+        Ok(tx_id)
+    }
+
+    /// Cancel a payment intent
+    pub fn cancel_payment_intent(
+        &mut self,
+        intent_id: FixedBytes<32>,
+    ) -> Result<(), OnClickContractError> {
+        let intent = self.paymentIntents.getter(intent_id);
+
+        // Check if intent exists
+        if intent.creator.get() == Address::ZERO {
+            return Err(OnClickContractError::PaymentIntentNotFound(
+                PaymentIntentNotFound {},
+            ));
+        }
+
+        // Check ownership
+        let page_id = intent.pageId.get();
+        let owner = self.pageAddresses.get(page_id);
+        self.check_page_permission(owner, page_id, PERMISSION_INTENTS)?;
+
+        self.paymentIntents.setter(intent_id).isActive.set(false);
+
+        log(
+            self.vm(),
+            PaymentIntentCancelled {
+                intentId: intent_id,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Update a payment intent's expiry (absolute timestamp), max usages
+    /// (0 = unlimited, otherwise not below the usage count) and description
+    pub fn update_payment_intent(
+        &mut self,
+        intent_id: FixedBytes<32>,
+        expires_at: U256,
+        max_usages: U256,
+        description: String,
+    ) -> Result<(), OnClickContractError> {
+        let intent = self.paymentIntents.getter(intent_id);
+
+        // Check if intent exists
+        if intent.creator.get() == Address::ZERO {
+            return Err(OnClickContractError::PaymentIntentNotFound(
+                PaymentIntentNotFound {},
+            ));
+        }
+
+        // Check ownership
+        let page_id = intent.pageId.get();
+        let owner = self.pageAddresses.get(page_id);
+        self.check_page_permission(owner, page_id, PERMISSION_INTENTS)?;
+
+        let timestamp = U256::from(self.vm().block_timestamp());
+        if expires_at <= timestamp {
+            return Err(OnClickContractError::InvalidExpiration(
+                InvalidExpiration {},
+            ));
+        }
+
+        let mut intent = self.paymentIntents.setter(intent_id);
+        if max_usages != U256::ZERO && max_usages < intent.usageCount.get() {
+            return Err(OnClickContractError::InvalidMaxUsages(InvalidMaxUsages {}));
+        }
+
+        intent.expiresAt.set(expires_at);
+        intent.maxUsages.set(max_usages);
+        intent.description.set_str(&description);
+        let is_active = intent.isActive.get();
+
+        log(
+            self.vm(),
+            PaymentIntentUpdated {
+                intentId: intent_id,
+                expiresAt: expires_at,
+                maxUsages: max_usages,
+                isActive: is_active,
+                description,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Reactivate a cancelled payment intent that has not expired
+    pub fn reactivate_payment_intent(
+        &mut self,
+        intent_id: FixedBytes<32>,
+    ) -> Result<(), OnClickContractError> {
+        let intent = self.paymentIntents.getter(intent_id);
+
+        // Check if intent exists
+        if intent.creator.get() == Address::ZERO {
+            return Err(OnClickContractError::PaymentIntentNotFound(
+                PaymentIntentNotFound {},
+            ));
+        }
+
+        // Check ownership
+        let page_id = intent.pageId.get();
+        let owner = self.pageAddresses.get(page_id);
+        self.check_page_permission(owner, page_id, PERMISSION_INTENTS)?;
+
+        let timestamp = U256::from(self.vm().block_timestamp());
+        let mut intent = self.paymentIntents.setter(intent_id);
+        if timestamp > intent.expiresAt.get() {
+            return Err(OnClickContractError::PaymentIntentExpired(
+                PaymentIntentExpired {},
+            ));
+        }
+
+        intent.isActive.set(true);
+        let event = PaymentIntentUpdated {
+            intentId: intent_id,
+            expiresAt: intent.expiresAt.get(),
+            maxUsages: intent.maxUsages.get(),
+            isActive: true,
+            description: intent.description.get_string(),
+        };
+
+        log(self.vm(), event);
+
+        Ok(())
+    }
+
+    /// Get payment intent details
+    pub fn get_payment_intent(
+        &self,
+        intent_id: FixedBytes<32>,
+    ) -> Result<PaymentIntentView, OnClickContractError> {
+        let intent = self.paymentIntents.getter(intent_id);
+
+        if intent.creator.get() == Address::ZERO {
+            return Err(OnClickContractError::PaymentIntentNotFound(
+                PaymentIntentNotFound {},
+            ));
+        }
+
+        // Report the page's current handle in case it changed
+        let page_owner = self.pageAddresses.get(intent.pageId.get());
+        let handle = if page_owner != Address::ZERO {
+            self.pages.getter(page_owner).handle.get_string()
+        } else {
+            intent.handle.get_string()
+        };
+
+        Ok(PaymentIntentView {
+            creator: intent.creator.get(),
+            handle,
+            amount: intent.amount.get(),
+            description: intent.description.get_string(),
+            isActive: intent.isActive.get(),
+            createdAt: intent.createdAt.get(),
+            expiresAt: intent.expiresAt.get(),
+            usageCount: intent.usageCount.get(),
+            maxUsages: intent.maxUsages.get(),
+        })
+    }
+
+    /// Get several payment intents. Each entry is a found flag and the intent
+    /// (zeroed when not found).
+    pub fn get_payment_intents(
+        &self,
+        intent_ids: Vec<FixedBytes<32>>,
+    ) -> Vec<(bool, PaymentIntentView)> {
+        let mut result = Vec::new();
+
+        for intent_id in intent_ids {
+            match self.get_payment_intent(intent_id) {
+                Ok(intent) => result.push((true, intent)),
+                Err(_) => result.push((false, PaymentIntentView::default())),
+            }
+        }
+
+        result
+    }
+
+    /// Get a page of payment intent IDs for a handle, newest first.
+    /// `offset` and `limit` select a window of intents and `status_mask` filters
+    /// within it as bit flags of `1 << PaymentIntentStatus` (0 = all), so a page
+    /// may hold fewer than `limit` ids; the next page starts at `offset + limit`.
+    pub fn get_payment_intents_by_handle(
+        &self,
+        handle: String,
+        offset: U256,
+        limit: U256,
+        status_mask: u8,
+    ) -> Vec<FixedBytes<32>> {
+        let owner = self.handleToAddress.get(handle);
+        self.get_payment_intents_by_address(owner, offset, limit, status_mask)
+    }
+
+    /// Get a page of payment intent IDs for an address, newest first.
+    /// Paged and filtered as in `get_payment_intents_by_handle`.
+    pub fn get_payment_intents_by_address(
+        &self,
+        user: Address,
+        offset: U256,
+        limit: U256,
+        status_mask: u8,
+    ) -> Vec<FixedBytes<32>> {
+        let page = self.pages.getter(user);
+        if page.owner.get() == Address::ZERO {
+            return Vec::new();
+        }
+
+        self.page_intent_ids(page.id.get(), offset, limit, status_mask)
+    }
+
+    /// Get a page of payment intents for a handle with their details, newest first.
+    /// Paged and filtered as in `get_payment_intents_by_handle`; each entry is
+    /// the intent id, the intent and its PaymentIntentStatus.
+    pub fn get_payment_intent_details_by_handle(
+        &self,
+        handle: String,
+        offset: U256,
+        limit: U256,
+        status_mask: u8,
+    ) -> Vec<(FixedBytes<32>, PaymentIntentView, u8)> {
+        let ids = self.get_payment_intents_by_handle(handle, offset, limit, status_mask);
+        let mut result = Vec::new();
+
+        for intent_id in ids {
+            let Ok(intent) = self.get_payment_intent(intent_id) else {
+                continue;
+            };
+            result.push((intent_id, intent, self.intent_status(intent_id)));
+        }
+
+        result
+    }
+
+    /// Get a payment intent's status (PaymentIntentStatus)
+    pub fn get_payment_intent_status(
+        &self,
+        intent_id: FixedBytes<32>,
+    ) -> Result<u8, OnClickContractError> {
+        if self.paymentIntents.getter(intent_id).creator.get() == Address::ZERO {
+            return Err(OnClickContractError::PaymentIntentNotFound(
+                PaymentIntentNotFound {},
+            ));
+        }
+
+        Ok(self.intent_status(intent_id))
+    }
+
+    /// Get payment intent count for a handle
+    pub fn get_payment_intent_count_by_handle(&self, handle: String) -> U256 {
+        let owner = self.handleToAddress.get(handle);
+        self.get_payment_intent_count_by_address(owner)
+    }
+
+    /// Get payment intent count for an address
+    pub fn get_payment_intent_count_by_address(&self, user: Address) -> U256 {
+        let page = self.pages.getter(user);
+        if page.owner.get() == Address::ZERO {
+            return U256::ZERO;
+        }
+
+        let intents = self.pagePaymentIntents.getter(page.id.get());
+        U256::from(intents.len())
+    }
+
+    // ==================== Invoices ====================
+
+    /// Issue an invoice from a Business page. Line items are given as parallel
+    /// lists; a zero `payer` lets anyone pay, and `late_fee` is added once overdue.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_invoice(
+        &mut self,
+        handle: String,
+        payer: Address,
+        description_hashes: Vec<FixedBytes<32>>,
+        quantities: Vec<U256>,
+        unit_prices: Vec<U256>,
+        due_date: U256,
+        late_fee: U256,
+    ) -> Result<U256, OnClickContractError> {
+        let sender = self.vm().msg_sender();
+        let owner = self.authorize_page(&handle, PERMISSION_INTENTS)?;
+
+        let page = self.pages.getter(owner);
+        if !has_capability(&page, CAPABILITY_PRODUCTS) {
+            return Err(OnClickContractError::CapabilityNotEnabled(
+                CapabilityNotEnabled {},
+            ));
+        }
+        let page_id = page.id.get();
+
+        let count = description_hashes.len();
+        if count == 0
+            || count > MAX_INVOICE_ITEMS
+            || count != quantities.len()
+            || count != unit_prices.len()
+        {
+            return Err(OnClickContractError::InvalidInvoice(InvalidInvoice {}));
+        }
+
+        let timestamp = U256::from(self.vm().block_timestamp());
+        if due_date <= timestamp {
+            return Err(OnClickContractError::InvalidExpiration(
+                InvalidExpiration {},
+            ));
+        }
+
+        let mut total = U256::ZERO;
+        for i in 0..count {
+            if quantities[i] == U256::ZERO {
+                return Err(OnClickContractError::InvalidInvoice(InvalidInvoice {}));
+            }
+            total = quantities[i]
+                .checked_mul(unit_prices[i])
+                .and_then(|amount| total.checked_add(amount))
+                .ok_or(OnClickContractError::InvalidInvoice(InvalidInvoice {}))?;
+        }
+        if total.checked_add(late_fee).is_none() {
+            return Err(OnClickContractError::InvalidInvoice(InvalidInvoice {}));
+        }
+
+        if total == U256::ZERO {
+            return Err(OnClickContractError::InvalidAmount(InvalidAmount {}));
+        }
+
+        let invoice_id = self.invoiceId.get();
+        self.invoiceId.set(invoice_id + U256::from(1));
+
+        let mut invoice = self.invoices.setter(invoice_id);
+        invoice.id.set(invoice_id);
+        invoice.pageId.set(page_id);
+        invoice.issuer.set(sender);
+        invoice.payer.set(payer);
+        invoice.total.set(total);
+        invoice.paid.set(U256::ZERO);
+        invoice.lateFee.set(late_fee);
+        invoice.dueDate.set(due_date);
+        invoice.status.set(U8::from(InvoiceStatus::Open as u8));
+        invoice.createdAt.set(timestamp);
+
+        let mut items = self.invoiceItems.setter(invoice_id);
+        for i in 0..count {
+            let mut item = items.grow();
+            item.descriptionHash.set(description_hashes[i]);
+            item.quantity.set(quantities[i]);
+            item.unitPrice.set(unit_prices[i]);
+        }
+
+        self.pageInvoices.setter(page_id).push(invoice_id);
+        if payer != Address::ZERO {
+            self.payerInvoices.setter(payer).push(invoice_id);
+        }
+
+        log(
+            self.vm(),
+            InvoiceCreated {
+                invoiceId: invoice_id,
+                business: owner,
+                payer,
+                total,
+                dueDate: due_date,
+            },
+        );
+
+        Ok(invoice_id)
+    }
+
+    /// Pay all or part of an invoice
+    #[payable]
+    pub fn pay_invoice(&mut self, invoice_id: U256) -> Result<U256, OnClickContractError> {
+        let amount = self.vm().msg_value();
+        if amount == U256::ZERO {
+            return Err(OnClickContractError::InvalidAmount(InvalidAmount {}));
+        }
+
+        let sender = self.vm().msg_sender();
+        let invoice = self.invoices.getter(invoice_id);
+
+        if invoice.issuer.get() == Address::ZERO {
+            return Err(OnClickContractError::InvoiceNotFound(InvoiceNotFound {}));
+        }
+
+        let payer = invoice.payer.get();
+        if payer != Address::ZERO && payer != sender {
+            return Err(OnClickContractError::NotInvoicePayer(NotInvoicePayer {}));
+        }
+
+        let status = self.invoice_status(invoice_id);
+        if status == InvoiceStatus::Paid as u8 || status == InvoiceStatus::Void as u8 {
+            return Err(OnClickContractError::InvoiceNotPayable(
+                InvoiceNotPayable {},
+            ));
+        }
+
+        let amount_due = self.get_invoice_amount_due(invoice_id)?;
+        if amount > amount_due {
+            return Err(OnClickContractError::InvalidAmount(InvalidAmount {}));
+        }
+
+        let invoice = self.invoices.getter(invoice_id);
+        let page_id = invoice.pageId.get();
+        let paid = invoice.paid.get() + amount;
+
+        let recipient = self.pageAddresses.get(page_id);
+        if recipient == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        let page = self.pages.getter(recipient);
+        if !page.isActive.get() {
+            return Err(OnClickContractError::PageNotActive(PageNotActive {}));
+        }
+
+        if page.isSuspended.get() {
+            return Err(OnClickContractError::PageUnderSuspension(
+                PageUnderSuspension {},
+            ));
+        }
+        let recipient_role = page.role.get();
+
+        // Update invoice
+        let remaining = amount_due - amount;
+        let mut invoice = self.invoices.setter(invoice_id);
+        invoice.paid.set(paid);
+        if remaining == U256::ZERO {
+            invoice.status.set(U8::from(InvoiceStatus::Paid as u8));
+        } else {
+            invoice
+                .status
+                .set(U8::from(InvoiceStatus::PartiallyPaid as u8));
+        }
+
+        // Take platform fee
+        let net_amount = self.take_fees(amount, recipient, Address::ZERO, U256::ZERO)?;
+
+        // Transfer funds
+        self.distribute_revenue(page_id, recipient, net_amount)?;
+
+        // Create transaction
+        let tx_id = self.transactionId.get();
+        self.transactionId.set(tx_id + U256::from(1));
+        let timestamp = U256::from(self.vm().block_timestamp());
+
+        self.pageTransactions.setter(page_id).push(tx_id);
+        self.record_page_stats(page_id, net_amount, true);
+        self.record_platform_volume(recipient, amount);
+
+        let mut transaction = self.transactions.setter(tx_id);
+        transaction.id.set(tx_id);
+        transaction.from.set(sender);
+        transaction.to.set(recipient);
+        transaction.amount.set(net_amount);
+        transaction.messageHash.set_str("");
+        transaction.recipientRole.set(recipient_role);
+        transaction.productId.set(U256::ZERO);
+        transaction.timestamp.set(timestamp);
+        transaction.isRefunded.set(false);
+        transaction.tip.set(U256::ZERO);
+        transaction.orderReference.set(FixedBytes::ZERO);
+        transaction.message.set_str("");
+        transaction.displayName.set_str("");
+        transaction.isAnonymous.set(false);
+
+        log(
+            self.vm(),
+            InvoicePaid {
+                invoiceId: invoice_id,
+                payer: sender,
+                amount,
+                remaining,
+            },
+        );
+
+        Ok(tx_id)
+    }
+
+    /// Void an invoice that has not been fully paid. Partial payments already
+    /// received are not refunded.
+    pub fn void_invoice(&mut self, invoice_id: U256) -> Result<(), OnClickContractError> {
+        let invoice = self.invoices.getter(invoice_id);
+
+        if invoice.issuer.get() == Address::ZERO {
+            return Err(OnClickContractError::InvoiceNotFound(InvoiceNotFound {}));
+        }
+
+        let page_id = invoice.pageId.get();
+        let owner = self.pageAddresses.get(page_id);
+        self.check_page_permission(owner, page_id, PERMISSION_INTENTS)?;
+
+        let status = self.invoice_status(invoice_id);
+        if status == InvoiceStatus::Paid as u8 || status == InvoiceStatus::Void as u8 {
+            return Err(OnClickContractError::InvoiceNotPayable(
+                InvoiceNotPayable {},
+            ));
+        }
+
+        self.invoices
+            .setter(invoice_id)
+            .status
+            .set(U8::from(InvoiceStatus::Void as u8));
+
+        log(
+            self.vm(),
+            InvoiceVoided {
+                invoiceId: invoice_id,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Get invoice details
+    /// (business, payer, total, paid, late fee, due date, status, created at)
+    pub fn get_invoice(
+        &self,
+        invoice_id: U256,
+    ) -> Result<(Address, Address, U256, U256, U256, U256, u8, U256), OnClickContractError> {
+        let invoice = self.invoices.getter(invoice_id);
+
+        if invoice.issuer.get() == Address::ZERO {
+            return Err(OnClickContractError::InvoiceNotFound(InvoiceNotFound {}));
+        }
+
+        Ok((
+            self.pageAddresses.get(invoice.pageId.get()),
+            invoice.payer.get(),
+            invoice.total.get(),
+            invoice.paid.get(),
+            invoice.lateFee.get(),
+            invoice.dueDate.get(),
+            self.invoice_status(invoice_id),
+            invoice.createdAt.get(),
+        ))
+    }
+
+    /// Get invoice line items (description hash, quantity, unit price)
+    pub fn get_invoice_items(
+        &self,
+        invoice_id: U256,
+    ) -> Result<Vec<(FixedBytes<32>, U256, U256)>, OnClickContractError> {
+        if self.invoices.getter(invoice_id).issuer.get() == Address::ZERO {
+            return Err(OnClickContractError::InvoiceNotFound(InvoiceNotFound {}));
+        }
+
+        let items = self.invoiceItems.getter(invoice_id);
+        let mut result = Vec::new();
+
+        for i in 0..items.len() {
+            let item = items.getter(i).unwrap();
+            result.push((
+                item.descriptionHash.get(),
+                item.quantity.get(),
+                item.unitPrice.get(),
+            ));
+        }
+
+        Ok(result)
+    }
+
+    /// Get the amount still owed on an invoice, including the late fee once overdue
+    pub fn get_invoice_amount_due(&self, invoice_id: U256) -> Result<U256, OnClickContractError> {
+        let invoice = self.invoices.getter(invoice_id);
+
+        if invoice.issuer.get() == Address::ZERO {
+            return Err(OnClickContractError::InvoiceNotFound(InvoiceNotFound {}));
+        }
+
+        let status = self.invoice_status(invoice_id);
+        if status == InvoiceStatus::Paid as u8 || status == InvoiceStatus::Void as u8 {
+            return Ok(U256::ZERO);
+        }
+
+        let mut total = invoice.total.get();
+        if status == InvoiceStatus::Overdue as u8 {
+            total += invoice.lateFee.get();
+        }
+
+        Ok(total.saturating_sub(invoice.paid.get()))
+    }
+
+    /// Get invoice IDs addressed to a payer newest first
+    pub fn get_invoices_by_payer(&self, payer: Address, offset: U256, limit: U256) -> Vec<U256> {
+        let invoices = self.payerInvoices.getter(payer);
+        let offset = offset.saturating_to::<usize>();
+        let limit = limit.saturating_to::<usize>().min(MAX_PAGE_SIZE);
+        let mut result = Vec::new();
+
+        for i in (0..invoices.len()).rev().skip(offset).take(limit) {
+            result.push(invoices.get(i).unwrap());
+        }
+
+        result
+    }
+
+    /// Get invoice IDs issued by a page newest first
+    pub fn get_invoices_by_handle(&self, handle: String, offset: U256, limit: U256) -> Vec<U256> {
+        let owner = self.handleToAddress.get(handle);
+        if owner == Address::ZERO {
+            return Vec::new();
+        }
+
+        let page_id = self.pages.getter(owner).id.get();
+        let invoices = self.pageInvoices.getter(page_id);
+        let offset = offset.saturating_to::<usize>();
+        let limit = limit.saturating_to::<usize>().min(MAX_PAGE_SIZE);
+        let mut result = Vec::new();
+
+        for i in (0..invoices.len()).rev().skip(offset).take(limit) {
+            result.push(invoices.get(i).unwrap());
+        }
+
+        result
+    }
+}
+
+impl OnClickContract {
+    /// Resolve a page owner by handle and check the caller may act on the page
+    fn authorize_page(
+        &self,
+        handle: &str,
+        permission: u8,
+    ) -> Result<Address, OnClickContractError> {
+        let owner = self.handleToAddress.get(String::from(handle));
+
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        let page_id = self.pages.getter(owner).id.get();
+        self.check_page_permission(owner, page_id, permission)?;
+
+        Ok(owner)
+    }
+
+    /// Check the caller is the page owner, or an operator holding `permission`
+    fn check_page_permission(
+        &self,
+        owner: Address,
+        page_id: U256,
+        permission: u8,
+    ) -> Result<(), OnClickContractError> {
+        let sender = self.vm().msg_sender();
+
+        if owner == Address::ZERO {
+            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
+        }
+
+        if sender == owner {
+            return Ok(());
+        }
+
+        let granted = self
+            .operatorPermissions
+            .getter(page_id)
+            .get(sender)
+            .to::<u8>();
+        if permission == OWNER_ONLY || granted & permission == 0 {
+            return Err(OnClickContractError::NotPageOwner(NotPageOwner {}));
+        }
+
+        Ok(())
+    }
+
+    /// Remove an operator from a page's operator list and permissions
+    fn remove_operator_entry(&mut self, page_id: U256, operator: Address) {
+        let mut operators = self.pageOperators.setter(page_id);
+        let len = operators.len();
+
+        for i in 0..len {
+            if operators.get(i).unwrap() == operator {
+                let last = operators.get(len - 1).unwrap();
+                operators.setter(i).unwrap().set(last);
+                operators.erase_last();
+                break;
+            }
+        }
+
+        self.operatorPermissions.setter(page_id).delete(operator);
+    }
+
+    /// Remove all operators of a page
+    fn clear_operators(&mut self, page_id: U256) {
+        while let Some(operator) = self.pageOperators.getter(page_id).get(0) {
+            self.remove_operator_entry(page_id, operator);
+        }
+    }
+
+    /// List revenue splits as (recipient, recipient page's current handle, share in bps)
+    fn split_views(&self, splits: &StorageVec<RevenueSplit>) -> Vec<(Address, String, U256)> {
+        let mut result = Vec::new();
+
+        for i in 0..splits.len() {
+            let split = splits.getter(i).unwrap();
+            let mut recipient_handle = String::new();
+            if split.isPage.get() {
+                let page_owner = self.pageAddresses.get(split.pageId.get());
+                recipient_handle = self.pages.getter(page_owner).handle.get_string();
+            }
+            result.push((split.recipient.get(), recipient_handle, split.bps.get()));
+        }
+
+        result
+    }
+
+    /// Credit a page's net income to its payout address, or to the page's
+    /// revenue split recipients when splits are configured
+    fn distribute_revenue(
+        &mut self,
+        page_id: U256,
+        owner: Address,
+        amount: U256,
+    ) -> Result<(), OnClickContractError> {
+        let count = self.pageSplits.getter(page_id).len();
+        if count == 0 {
+            let payout = self.payout_address_of(owner);
+            self.credit_balance(payout, amount);
+            return Ok(());
+        }
+
+        let mut remaining = amount;
+        for i in 0..count {
+            let splits = self.pageSplits.getter(page_id);
+            let split = splits.getter(i).unwrap();
+            let is_page = split.isPage.get();
+            let recipient_page = split.pageId.get();
+            let bps = split.bps.get();
+
+            let mut recipient = split.recipient.get();
+            if is_page {
+                let mut page_owner = self.pageAddresses.get(recipient_page);
+                if page_owner == Address::ZERO {
+                    // Released page: the share stays with this page
+                    page_owner = owner;
+                }
+                recipient = self.payout_address_of(page_owner);
+            }
 
-        evm::log(PlatformFeeUpdated {
-            newFee: fee_percentage,
-        });
+            // The last recipient also receives any rounding remainder
+            let share = if i == count - 1 {
+                remaining
+            } else {
+                (amount * bps) / U256::from(10000)
+            };
+            remaining -= share;
+
+            self.credit_balance(recipient, share);
+
+            log(
+                self.vm(),
+                SplitPaid {
+                    owner,
+                    recipient,
+                    amount: share,
+                },
+            );
+        }
 
         Ok(())
     }
 
-    /// Withdraw platform fees (admin only)
-    pub fn withdraw_platform_fees(&mut self) -> Result<(), OnClickContractError> {
-        let sender = self.vm().msg_sender();
-        if sender != self.owner.get() {
-            return Err(OnClickContractError::NotPageOwner(NotPageOwner {}));
+    /// Effective (min delay, max age) for handle commitments
+    fn handle_commit_window(&self) -> (U256, U256) {
+        let mut min_delay = self.handleCommitMinDelay.get();
+        if min_delay == U256::ZERO {
+            min_delay = U256::from(DEFAULT_HANDLE_COMMIT_MIN_DELAY);
         }
 
-        let fees = self.platformFeesCollected.get();
-        self.platformFeesCollected.set(U256::ZERO);
+        let mut max_age = self.handleCommitMaxAge.get();
+        if max_age == U256::ZERO {
+            max_age = U256::from(DEFAULT_HANDLE_COMMIT_MAX_AGE);
+        }
 
-        self.vm().transfer_eth(sender, fees)?;
+        (min_delay, max_age)
+    }
 
-        evm::log(PlatformFeesWithdrawn { amount: fees });
+    /// Set the active flag of the caller's page
+    fn set_page_active(
+        &mut self,
+        handle: String,
+        active: bool,
+    ) -> Result<(), OnClickContractError> {
+        let owner = self.authorize_page(&handle, OWNER_ONLY)?;
 
-        Ok(())
-    }
+        self.pages.setter(owner).isActive.set(active);
 
-    /// Get next ID (utility function)
-    pub fn get_next_id(&self) -> U256 {
-        self.pageId.get()
-    }
+        if active {
+            log(self.vm(), PageReactivated { owner, handle });
+        } else {
+            log(self.vm(), PageDeactivated { owner, handle });
+        }
 
-    // ==================== Payment Intent Functions ====================
+        Ok(())
+    }
 
-    /// Create a payment intent (payment link)
-    pub fn create_payment_intent(
+    /// Store a new payment intent for a page; `amount` is zero for open-amount intents
+    fn create_intent(
         &mut self,
+        handle: String,
         amount: U256,
         description: String,
         expires_in_seconds: U256,
         max_usages: U256,
+        salt: FixedBytes<32>,
     ) -> Result<FixedBytes<32>, OnClickContractError> {
         let sender = self.vm().msg_sender();
-        let page = self.pages.getter(sender);
-
-        if page.owner.get() == Address::ZERO {
-            return Err(OnClickContractError::PageNotFound(PageNotFound {}));
-        }
-
-        if amount == U256::ZERO {
-            return Err(OnClickContractError::InvalidAmount(InvalidAmount {}));
-        }
+        let owner = self.authorize_page(&handle, PERMISSION_INTENTS)?;
+        let page = self.pages.getter(owner);
+        let page_id = page.id.get();
 
         let timestamp = self.vm().block_timestamp();
         if expires_in_seconds == U256::ZERO {
@@ -924,17 +3991,21 @@ impl OnClickContract {
             ));
         }
 
-        // Generate unique intent ID
-        let mut data = Vec::new();
-        data.extend_from_slice(sender.as_slice());
-        data.extend_from_slice(&timestamp.to_le_bytes());
-        data.extend_from_slice(&amount.to_le_bytes::<32>());
-
-        let intent_id = crypto::keccak(data);
-
         let expires_at = U256::from(timestamp) + expires_in_seconds;
         let handle = page.handle.get_string();
 
+        // Generate unique intent ID from the page's nonce and the caller's salt
+        let nonce = self.intentNonces.get(page_id);
+        let intent_id = payment_intent_id(page_id, nonce, salt);
+
+        if self.paymentIntents.getter(intent_id).creator.get() != Address::ZERO {
+            return Err(OnClickContractError::PaymentIntentAlreadyExists(
+                PaymentIntentAlreadyExists {},
+            ));
+        }
+
+        self.intentNonces.setter(page_id).set(nonce + U256::from(1));
+
         // Store payment intent
         let mut intent = self.paymentIntents.setter(intent_id);
         intent.id.set(intent_id);
@@ -947,231 +4018,719 @@ impl OnClickContract {
         intent.expiresAt.set(expires_at);
         intent.usageCount.set(U256::ZERO);
         intent.maxUsages.set(max_usages);
+        intent.pageId.set(page_id);
 
-        // Add to handle and user mappings
-        self.handlePaymentIntents
-            .setter(handle.clone())
-            .push(intent_id);
-        self.userPaymentIntents.setter(sender).push(intent_id);
+        // Add to page index
+        self.pagePaymentIntents.setter(page_id).push(intent_id);
 
-        evm::log(PaymentIntentCreated {
-            intentId: intent_id,
-            creator: sender,
-            handle: handle,
-            amount,
-        });
+        log(
+            self.vm(),
+            PaymentIntentCreated {
+                intentId: intent_id,
+                creator: sender,
+                handle,
+                amount,
+            },
+        );
 
         Ok(intent_id)
     }
 
-    /// Pay via payment intent
-    #[payable]
-    pub fn pay_intent(
-        &mut self,
-        intent_id: FixedBytes<32>,
-        message_hash: String,
-    ) -> Result<U256, OnClickContractError> {
-        let amount = self.vm().msg_value();
-        if amount == U256::ZERO {
-            return Err(OnClickContractError::InvalidAmount(InvalidAmount {}));
+    /// Scan a window of a page's payment intents newest first, keeping those
+    /// whose status is selected by `status_mask` (0 = all)
+    fn page_intent_ids(
+        &self,
+        page_id: U256,
+        offset: U256,
+        limit: U256,
+        status_mask: u8,
+    ) -> Vec<FixedBytes<32>> {
+        let intents = self.pagePaymentIntents.getter(page_id);
+        let offset = offset.saturating_to::<usize>();
+        let limit = limit.saturating_to::<usize>().min(MAX_PAGE_SIZE);
+        let mut result = Vec::new();
+
+        for i in (0..intents.len()).rev().skip(offset).take(limit) {
+            let intent_id = intents.get(i).unwrap();
+            if status_mask != 0 && status_mask & (1 << self.intent_status(intent_id)) == 0 {
+                continue;
+            }
+
+            result.push(intent_id);
         }
 
-        let sender = self.vm().msg_sender();
-        let current_time = U256::from(self.vm().block_timestamp());
+        result
+    }
 
-        let mut intent = self.paymentIntents.setter(intent_id);
+    /// Current status of a payment intent
+    fn intent_status(&self, intent_id: FixedBytes<32>) -> u8 {
+        let intent = self.paymentIntents.getter(intent_id);
 
-        // Check if intent exists
-        if intent.creator.get() == Address::ZERO {
-            return Err(OnClickContractError::PaymentIntentNotFound(
-                PaymentIntentNotFound {},
-            ));
+        if !intent.isActive.get() {
+            return PaymentIntentStatus::Cancelled as u8;
         }
 
-        // Check if active
-        if !intent.isActive.get() {
-            return Err(OnClickContractError::PaymentIntentInactive(
-                PaymentIntentInactive {},
-            ));
+        if U256::from(self.vm().block_timestamp()) > intent.expiresAt.get() {
+            return PaymentIntentStatus::Expired as u8;
         }
 
-        // Check expiration
-        let expires_at = intent.expiresAt.get();
-        if current_time > expires_at {
-            return Err(OnClickContractError::PaymentIntentExpired(
-                PaymentIntentExpired {},
+        let max_usages = intent.maxUsages.get();
+        let total_cap = intent.totalCap.get();
+        if (max_usages != U256::ZERO && intent.usageCount.get() >= max_usages)
+            || (total_cap != U256::ZERO && intent.totalCollected.get() >= total_cap)
+        {
+            return PaymentIntentStatus::Exhausted as u8;
+        }
+
+        PaymentIntentStatus::Active as u8
+    }
+
+    /// Price of an active product sold by a page that can sell products
+    fn linked_product_price(
+        &self,
+        page_id: U256,
+        product_id: U256,
+    ) -> Result<U256, OnClickContractError> {
+        let owner = self.pageAddresses.get(page_id);
+        if !has_capability(&self.pages.getter(owner), CAPABILITY_PRODUCTS) {
+            return Err(OnClickContractError::CapabilityNotEnabled(
+                CapabilityNotEnabled {},
             ));
         }
 
-        // Check max usages and get values before mutation
-        let max_usages = intent.maxUsages.get();
-        let expected_amount = intent.amount.get();
-        let recipient = intent.creator.get();
-
-        if max_usages != U256::ZERO {
-            let usage_count = intent.usageCount.get();
-            if usage_count >= max_usages {
-                return Err(OnClickContractError::PaymentIntentMaxUsages(
-                    PaymentIntentMaxUsages {},
-                ));
-            }
-            intent.usageCount.set(usage_count + U256::from(1));
+        let product = self.products.getter(product_id);
+        if product.businessOwner.get() == Address::ZERO || product.pageId.get() != page_id {
+            return Err(OnClickContractError::ProductNotFound(ProductNotFound {}));
         }
 
-        // Verify amount matches
-        if amount != expected_amount {
-            return Err(OnClickContractError::InvalidAmount(InvalidAmount {}));
+        if !product.isActive.get() {
+            return Err(OnClickContractError::ProductNotActive(ProductNotActive {}));
         }
 
-        // Get recipient page for role validation
-        let page = self.pages.getter(recipient);
-        if !page.isActive.get() {
-            return Err(OnClickContractError::PageNotActive(PageNotActive {}));
+        Ok(product.price.get())
+    }
+
+    /// Current invoice status; open invoices past their due date are overdue
+    fn invoice_status(&self, invoice_id: U256) -> u8 {
+        let invoice = self.invoices.getter(invoice_id);
+        let status = invoice.status.get().to::<u8>();
+
+        if status == InvoiceStatus::Paid as u8 || status == InvoiceStatus::Void as u8 {
+            return status;
         }
-        let recipient_role = page.role.get();
 
-        // Calculate platform fee
-        let fee = (amount * self.platformFee.get()) / U256::from(10000);
-        let net_amount = amount - fee;
+        if U256::from(self.vm().block_timestamp()) > invoice.dueDate.get() {
+            return InvoiceStatus::Overdue as u8;
+        }
 
-        // Update page stats
-        let mut page_mut = self.pages.setter(recipient);
-        let raised = page_mut.raised.get();
-        page_mut.raised.set(raised + net_amount);
-        let supporters = page_mut.supporters.get();
-        page_mut.supporters.set(supporters + U256::from(1));
+        status
+    }
 
-        // Transfer funds
-        self.vm().transfer_eth(recipient, net_amount)?;
+    /// Take the platform fee and the integrator's fee (credited to its balance)
+    /// from a payment, returning the net amount for the page
+    fn take_fees(
+        &mut self,
+        amount: U256,
+        recipient: Address,
+        integrator: Address,
+        integrator_fee_bps: U256,
+    ) -> Result<U256, OnClickContractError> {
+        if integrator_fee_bps > self.maxIntegratorFee.get() {
+            return Err(OnClickContractError::IntegratorFeeTooHigh(
+                IntegratorFeeTooHigh {},
+            ));
+        }
+
+        let fee = (amount * self.platformFee.get()) / U256::from(10000);
+        let mut integrator_fee = U256::ZERO;
+        if integrator != Address::ZERO {
+            integrator_fee = (amount * integrator_fee_bps) / U256::from(10000);
+        }
+
+        let net_amount = amount
+            .checked_sub(fee + integrator_fee)
+            .ok_or(OnClickContractError::InvalidAmount(InvalidAmount {}))?;
 
         // Update platform fees
         let fees = self.platformFeesCollected.get();
         self.platformFeesCollected.set(fees + fee);
+        let total_fees = self.totalFeesCollected.get();
+        self.totalFeesCollected.set(total_fees + fee);
+
+        if integrator_fee > U256::ZERO {
+            self.credit_balance(integrator, integrator_fee);
+
+            log(
+                self.vm(),
+                IntegratorFeePaid {
+                    integrator,
+                    recipient,
+                    amount: integrator_fee,
+                },
+            );
+        }
 
-        // Create transaction
-        let tx_id = self.transactionId.get();
-        self.transactionId.set(tx_id + U256::from(1));
-        let timestamp = U256::from(self.vm().block_timestamp());
+        Ok(net_amount)
+    }
 
-        let mut transaction = self.transactions.setter(tx_id);
-        transaction.id.set(tx_id);
-        transaction.from.set(sender);
-        transaction.to.set(recipient);
-        transaction.amount.set(net_amount);
-        transaction.messageHash.set_str(&message_hash);
-        transaction.recipientRole.set(recipient_role);
-        transaction.productId.set(U256::ZERO);
-        transaction.timestamp.set(timestamp);
-        transaction.isRefunded.set(false);
+    /// Record a merchant order reference for a page, rejecting one already used
+    fn use_order_reference(
+        &mut self,
+        page_id: U256,
+        order_reference: FixedBytes<32>,
+        tx_id: U256,
+    ) -> Result<(), OnClickContractError> {
+        if order_reference == FixedBytes::ZERO {
+            return Ok(());
+        }
 
-        // Emit events
-        evm::log(PaymentIntentPaid {
-            intentId: intent_id,
-            payer: sender,
-            amount: net_amount,
-        });
+        let mut order_references = self.pageOrderReferences.setter(page_id);
+        let mut stored = order_references.setter(order_reference);
+        if stored.get() != U256::ZERO {
+            return Err(OnClickContractError::ReferenceAlreadyUsed(
+                ReferenceAlreadyUsed {},
+            ));
+        }
+        stored.set(tx_id + U256::from(1));
 
-        evm::log(DonationMade {
-            from: sender,
-            to: recipient,
-            amount: net_amount,
-            messageHash: message_hash,
-        });
+        Ok(())
+    }
 
-        Ok(tx_id)
+    /// Add a payment to a supporter's record, counting each supporter once per page.
+    /// Anonymous payments leave the leaderboard untouched so no event links them
+    /// to the supporter.
+    fn record_supporter(
+        &mut self,
+        page_id: U256,
+        owner: Address,
+        supporter: Address,
+        amount: U256,
+        anonymous: bool,
+    ) {
+        let mut records = self.supporterRecords.setter(page_id);
+        let mut record = records.setter(supporter);
+        let payments = record.payments.get();
+        let total = record.total.get() + amount;
+        record.total.set(total);
+        record.payments.set(payments + U256::from(1));
+        if !anonymous {
+            record.isPublic.set(true);
+        }
+
+        if payments == U256::ZERO {
+            self.pageSupporters.setter(page_id).push(supporter);
+
+            let mut page = self.pages.setter(owner);
+            let supporters = page.supporters.get();
+            page.supporters.set(supporters + U256::from(1));
+        }
+
+        if !anonymous {
+            self.update_leaderboard(page_id, owner, supporter, total);
+        }
     }
 
-    /// Cancel a payment intent
-    pub fn cancel_payment_intent(
+    /// Move a supporter to their place on the page leaderboard for a new total
+    fn update_leaderboard(
         &mut self,
-        intent_id: FixedBytes<32>,
+        page_id: U256,
+        owner: Address,
+        supporter: Address,
+        total: U256,
+    ) {
+        let records = self.supporterRecords.getter(page_id);
+        let mut board = self.leaderboards.setter(page_id);
+        let len = board.len();
+
+        let mut rank = len;
+        for i in 0..len {
+            if board.get(i).unwrap() == supporter {
+                rank = i;
+                break;
+            }
+        }
+
+        // Enter the board, displacing the lowest entry when full
+        if rank == len {
+            if len < MAX_LEADERBOARD_SIZE {
+                board.push(supporter);
+            } else {
+                rank = len - 1;
+                let lowest = board.get(rank).unwrap();
+                if records.getter(lowest).total.get() >= total {
+                    return;
+                }
+                board.setter(rank).unwrap().set(supporter);
+            }
+        }
+
+        while rank > 0 {
+            let above = board.get(rank - 1).unwrap();
+            if records.getter(above).total.get() >= total {
+                break;
+            }
+            board.setter(rank).unwrap().set(above);
+            board.setter(rank - 1).unwrap().set(supporter);
+            rank -= 1;
+        }
+
+        log(
+            self.vm(),
+            LeaderboardChanged {
+                owner,
+                supporter,
+                rank: U256::from(rank),
+                total,
+            },
+        );
+    }
+
+    /// Add a payment to a page's raised amount, counters and daily buckets;
+    /// `is_sale` also counts it as sales revenue and an order
+    fn record_page_stats(&mut self, page_id: U256, amount: U256, is_sale: bool) {
+        let owner = self.pageAddresses.get(page_id);
+        let mut page = self.pages.setter(owner);
+        let raised = page.raised.get();
+        page.raised.set(raised + amount);
+
+        let day = U256::from(self.vm().block_timestamp() / 86_400);
+        let mut daily_revenue = self.pageDailyRevenue.setter(page_id);
+        let mut day_revenue = daily_revenue.setter(day);
+        let revenue = day_revenue.get();
+        day_revenue.set(revenue + amount);
+        let mut daily_payments = self.pageDailyPayments.setter(page_id);
+        let mut day_payments = daily_payments.setter(day);
+        let payments = day_payments.get();
+        day_payments.set(payments + U256::from(1));
+
+        let mut stats = self.pageStats.setter(page_id);
+        let total_raised = stats.totalRaised.get();
+        stats.totalRaised.set(total_raised + amount);
+        let transaction_count = stats.transactionCount.get();
+        stats
+            .transactionCount
+            .set(transaction_count + U256::from(1));
+
+        if is_sale {
+            let total_revenue = stats.totalRevenue.get();
+            stats.totalRevenue.set(total_revenue + amount);
+            let total_orders = stats.totalOrders.get();
+            stats.totalOrders.set(total_orders + U256::from(1));
+        }
+    }
+
+    /// Add a payment's gross amount to the platform volume counters
+    fn record_platform_volume(&mut self, recipient: Address, amount: U256) {
+        let role = self.pages.getter(recipient).role.get();
+        let mut role_volume = self.volumeByRole.setter(role);
+        let volume = role_volume.get();
+        role_volume.set(volume + amount);
+
+        let day = U256::from(self.vm().block_timestamp() / 86_400);
+        let mut day_volume = self.dailyVolume.setter(day);
+        let volume = day_volume.get();
+        day_volume.set(volume + amount);
+    }
+
+    /// Credit an address's withdrawable balance
+    fn credit_balance(&mut self, account: Address, amount: U256) {
+        let balance = self.pendingWithdrawals.get(account);
+        self.pendingWithdrawals
+            .setter(account)
+            .set(balance + amount);
+    }
+
+    /// Address a page's income is paid to
+    fn payout_address_of(&self, owner: Address) -> Address {
+        let payout = self.pages.getter(owner).payoutAddress.get();
+        if payout == Address::ZERO {
+            owner
+        } else {
+            payout
+        }
+    }
+
+    /// Move a page record to a new owner address. Payout settings are carried
+    /// over; with a timelock set, a page paying its owner keeps paying the old
+    /// owner, so a transfer cannot bypass the timelock to redirect income.
+    fn move_page(&mut self, from: Address, to: Address) {
+        let page = self.pages.getter(from);
+        let handle = page.handle.get_string();
+        let role = page.role.get();
+        let name = page.name.get_string();
+        let metadata_hash = page.metadataHash.get_string();
+        let goal = page.goal.get();
+        let raised = page.raised.get();
+        let supporters = page.supporters.get();
+        let is_active = page.isActive.get();
+        let created_at = page.createdAt.get();
+        let id = page.id.get();
+        let is_suspended = page.isSuspended.get();
+        let suspension_reason = page.suspensionReason.get();
+        let capabilities = page.capabilities.get();
+        let affiliate_bps = page.affiliateBps.get();
+        let overpayment_policy = page.overpaymentPolicy.get();
+        let campaign_deadline = page.campaignDeadline.get();
+        let mut payout_address = page.payoutAddress.get();
+        let pending_payout_address = page.pendingPayoutAddress.get();
+        let payout_change_available_at = page.payoutChangeAvailableAt.get();
+        let payout_timelock = page.payoutTimelock.get();
+        if payout_address == Address::ZERO && payout_timelock != U256::ZERO {
+            payout_address = from;
+        }
+
+        let mut moved = self.pages.setter(to);
+        moved.owner.set(to);
+        moved.handle.set_str(&handle);
+        moved.role.set(role);
+        moved.name.set_str(&name);
+        moved.metadataHash.set_str(&metadata_hash);
+        moved.goal.set(goal);
+        moved.raised.set(raised);
+        moved.supporters.set(supporters);
+        moved.isActive.set(is_active);
+        moved.createdAt.set(created_at);
+        moved.id.set(id);
+        moved.isSuspended.set(is_suspended);
+        moved.suspensionReason.set(suspension_reason);
+        moved.capabilities.set(capabilities);
+        moved.affiliateBps.set(affiliate_bps);
+        moved.overpaymentPolicy.set(overpayment_policy);
+        moved.campaignDeadline.set(campaign_deadline);
+        moved.payoutAddress.set(payout_address);
+        moved.pendingPayoutAddress.set(pending_payout_address);
+        moved
+            .payoutChangeAvailableAt
+            .set(payout_change_available_at);
+        moved.payoutTimelock.set(payout_timelock);
+
+        self.pages.delete(from);
+    }
+
+    /// Check and consume the commitment for `handle` made by `owner` with `salt`
+    fn consume_handle_commitment(
+        &mut self,
+        handle: &str,
+        owner: Address,
+        salt: FixedBytes<32>,
     ) -> Result<(), OnClickContractError> {
-        let sender = self.vm().msg_sender();
-        let mut intent = self.paymentIntents.setter(intent_id);
+        let commitment = self.compute_handle_commitment(String::from(handle), owner, salt);
+        let committed_at = self.handleCommitments.getter(owner).get(commitment);
 
-        // Check if intent exists
-        if intent.creator.get() == Address::ZERO {
-            return Err(OnClickContractError::PaymentIntentNotFound(
-                PaymentIntentNotFound {},
+        if committed_at == U256::ZERO {
+            return Err(OnClickContractError::HandleCommitmentNotFound(
+                HandleCommitmentNotFound {},
             ));
         }
 
-        // Check ownership
-        if intent.creator.get() != sender {
-            return Err(OnClickContractError::NotPageOwner(NotPageOwner {}));
+        let now = U256::from(self.vm().block_timestamp());
+        let (min_delay, max_age) = self.handle_commit_window();
+
+        if now < committed_at + min_delay {
+            return Err(OnClickContractError::HandleCommitmentTooRecent(
+                HandleCommitmentTooRecent {},
+            ));
         }
 
-        intent.isActive.set(false);
+        if now > committed_at + max_age {
+            return Err(OnClickContractError::HandleCommitmentExpired(
+                HandleCommitmentExpired {},
+            ));
+        }
 
-        evm::log(PaymentIntentCancelled {
-            intentId: intent_id,
-        });
+        self.handleCommitments.setter(owner).delete(commitment);
 
         Ok(())
     }
+}
 
-    /// Get payment intent details
-    pub fn get_payment_intent(
-        &self,
-        intent_id: FixedBytes<32>,
-    ) -> Result<(Address, String, U256, String, bool, U256, U256, U256, U256), OnClickContractError>
-    {
-        let intent = self.paymentIntents.getter(intent_id);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stylus_sdk::testing::*;
+
+    const SELLER: Address = Address::repeat_byte(0x11);
+    const BUYER: Address = Address::repeat_byte(0x22);
+    const OPERATOR: Address = Address::repeat_byte(0x33);
+    const PAYEE: Address = Address::repeat_byte(0x44);
+
+    const SALT: FixedBytes<32> = FixedBytes::repeat_byte(0x01);
+    const COMMITTED_AT: u64 = 1_000;
+    const REVEAL_AT: u64 = COMMITTED_AT + DEFAULT_HANDLE_COMMIT_MIN_DELAY;
+
+    /// Commit to `handle` as `owner`
+    fn commit(vm: &TestVM, contract: &mut OnClickContract, owner: Address, handle: &str) {
+        vm.set_sender(owner);
+        let commitment = contract.compute_handle_commitment(String::from(handle), owner, SALT);
+        assert!(contract.commit_handle(commitment).is_ok());
+    }
 
-        if intent.creator.get() == Address::ZERO {
-            return Err(OnClickContractError::PaymentIntentNotFound(
-                PaymentIntentNotFound {},
-            ));
-        }
+    /// Reveal `owner`'s commitment to `handle` and register a Business page
+    fn register(
+        vm: &TestVM,
+        contract: &mut OnClickContract,
+        owner: Address,
+        handle: &str,
+    ) -> Result<U256, OnClickContractError> {
+        vm.set_sender(owner);
+        contract.register_user(
+            String::from(handle),
+            UserRole::Business as u8,
+            String::from("Store"),
+            String::new(),
+            SALT,
+        )
+    }
 
-        Ok((
-            intent.creator.get(),
-            intent.handle.get_string(),
-            intent.amount.get(),
-            intent.description.get_string(),
-            intent.isActive.get(),
-            intent.createdAt.get(),
-            intent.expiresAt.get(),
-            intent.usageCount.get(),
-            intent.maxUsages.get(),
-        ))
+    /// Register a Business page for `SELLER` with one product at `price`
+    fn setup_store(vm: &TestVM, price: U256) -> (OnClickContract, U256) {
+        let mut contract = OnClickContract::from(vm);
+
+        vm.set_block_timestamp(COMMITTED_AT);
+        commit(vm, &mut contract, SELLER, "store");
+        vm.set_block_timestamp(REVEAL_AT);
+        assert!(register(vm, &mut contract, SELLER, "store").is_ok());
+
+        let product_id = contract
+            .create_product(
+                String::from("store"),
+                String::from("Item"),
+                price,
+                String::new(),
+            )
+            .unwrap_or_else(|_| panic!("create_product failed"));
+
+        (contract, product_id)
     }
 
-    /// Get all payment intent IDs for a handle
-    pub fn get_payment_intents_by_handle(&self, handle: String) -> Vec<FixedBytes<32>> {
-        let intents = self.handlePaymentIntents.getter(handle);
-        let len = intents.len();
-        let mut result = Vec::new();
+    fn purchase(
+        contract: &mut OnClickContract,
+        product_id: U256,
+        tip: U256,
+    ) -> Result<U256, OnClickContractError> {
+        contract.purchase_product(
+            String::from("store"),
+            product_id,
+            Address::ZERO,
+            FixedBytes::ZERO,
+            tip,
+            Address::ZERO,
+            U256::ZERO,
+        )
+    }
 
-        for i in 0..len {
-            result.push(intents.get(i).unwrap());
-        }
+    #[test]
+    fn purchase_credits_price_and_tip() {
+        let vm = TestVM::default();
+        let (mut contract, product_id) = setup_store(&vm, U256::from(1_000));
 
-        result
+        vm.set_sender(BUYER);
+        vm.set_value(U256::from(1_100));
+        assert!(purchase(&mut contract, product_id, U256::from(100)).is_ok());
+
+        let balance = contract.get_page_balance(String::from("store"));
+        assert!(matches!(balance, Ok(amount) if amount == U256::from(1_100)));
     }
 
-    /// Get all payment intent IDs for an address
-    pub fn get_payment_intents_by_address(&self, user: Address) -> Vec<FixedBytes<32>> {
-        let intents = self.userPaymentIntents.getter(user);
-        let len = intents.len();
-        let mut result = Vec::new();
+    #[test]
+    fn purchase_rejects_overflowing_tip() {
+        let vm = TestVM::default();
+        let (mut contract, product_id) = setup_store(&vm, U256::MAX);
+
+        vm.set_sender(BUYER);
+        vm.set_value(U256::from(1));
+        let result = purchase(&mut contract, product_id, U256::from(2));
+        assert!(matches!(
+            result,
+            Err(OnClickContractError::InvalidAmount(_))
+        ));
+    }
 
-        for i in 0..len {
-            result.push(intents.get(i).unwrap());
-        }
+    #[test]
+    fn reveal_waits_for_commit_delay() {
+        let vm = TestVM::default();
+        let mut contract = OnClickContract::from(&vm);
 
-        result
+        vm.set_block_timestamp(COMMITTED_AT);
+        commit(&vm, &mut contract, SELLER, "store");
+
+        vm.set_block_timestamp(REVEAL_AT - 1);
+        let result = register(&vm, &mut contract, SELLER, "store");
+        assert!(matches!(
+            result,
+            Err(OnClickContractError::HandleCommitmentTooRecent(_))
+        ));
+
+        vm.set_block_timestamp(REVEAL_AT);
+        assert!(register(&vm, &mut contract, SELLER, "store").is_ok());
     }
 
-    /// Get payment intent count for a handle
-    pub fn get_payment_intent_count_by_handle(&self, handle: String) -> U256 {
-        let intents = self.handlePaymentIntents.getter(handle);
-        U256::from(intents.len())
+    #[test]
+    fn reveal_rejects_expired_commitment() {
+        let vm = TestVM::default();
+        let mut contract = OnClickContract::from(&vm);
+
+        vm.set_block_timestamp(COMMITTED_AT);
+        commit(&vm, &mut contract, SELLER, "store");
+
+        vm.set_block_timestamp(COMMITTED_AT + DEFAULT_HANDLE_COMMIT_MAX_AGE + 1);
+        let result = register(&vm, &mut contract, SELLER, "store");
+        assert!(matches!(
+            result,
+            Err(OnClickContractError::HandleCommitmentExpired(_))
+        ));
     }
 
-    /// Get payment intent count for an address
-    pub fn get_payment_intent_count_by_address(&self, user: Address) -> U256 {
-        let intents = self.userPaymentIntents.getter(user);
-        U256::from(intents.len())
+    #[test]
+    fn commitment_is_bound_to_committer() {
+        let vm = TestVM::default();
+        let mut contract = OnClickContract::from(&vm);
+
+        vm.set_block_timestamp(COMMITTED_AT);
+        commit(&vm, &mut contract, SELLER, "store");
+
+        // Replaying the seller's commitment neither resets it nor lets the
+        // replayer reveal the handle
+        let commitment = contract.compute_handle_commitment(String::from("store"), SELLER, SALT);
+        vm.set_sender(BUYER);
+        vm.set_block_timestamp(REVEAL_AT - 1);
+        assert!(contract.commit_handle(commitment).is_ok());
+
+        vm.set_block_timestamp(REVEAL_AT);
+        let result = register(&vm, &mut contract, BUYER, "store");
+        assert!(matches!(
+            result,
+            Err(OnClickContractError::HandleCommitmentNotFound(_))
+        ));
+        assert!(register(&vm, &mut contract, SELLER, "store").is_ok());
+    }
+
+    #[test]
+    fn change_handle_reveals_commitment() {
+        let vm = TestVM::default();
+        let (mut contract, _) = setup_store(&vm, U256::from(1_000));
+
+        let result = contract.change_handle(String::from("store"), String::from("shop"), SALT);
+        assert!(matches!(
+            result,
+            Err(OnClickContractError::HandleCommitmentNotFound(_))
+        ));
+
+        commit(&vm, &mut contract, SELLER, "shop");
+        vm.set_block_timestamp(REVEAL_AT + DEFAULT_HANDLE_COMMIT_MIN_DELAY);
+        let result = contract.change_handle(String::from("store"), String::from("shop"), SALT);
+        assert!(result.is_ok());
+        assert_eq!(contract.handleToAddress.get(String::from("shop")), SELLER);
+    }
+
+    #[test]
+    fn operator_is_limited_to_granted_permissions() {
+        let vm = TestVM::default();
+        let (mut contract, _) = setup_store(&vm, U256::from(1_000));
+        let store = String::from("store");
+
+        assert!(contract
+            .add_operator(store.clone(), OPERATOR, PERMISSION_INTENTS)
+            .is_ok());
+
+        vm.set_sender(OPERATOR);
+        let result = contract.create_product(
+            store.clone(),
+            String::from("Item"),
+            U256::from(1),
+            String::new(),
+        );
+        assert!(matches!(result, Err(OnClickContractError::NotPageOwner(_))));
+
+        let result = contract.withdraw_funds(store.clone(), U256::from(1));
+        assert!(matches!(result, Err(OnClickContractError::NotPageOwner(_))));
+
+        let result = contract.set_payout_address(store.clone(), OPERATOR);
+        assert!(matches!(result, Err(OnClickContractError::NotPageOwner(_))));
+
+        let result = contract.set_store_affiliate_bps(store, U256::from(10_000));
+        assert!(matches!(result, Err(OnClickContractError::NotPageOwner(_))));
+    }
+
+    #[test]
+    fn payout_address_change_waits_for_timelock() {
+        let vm = TestVM::default();
+        let (mut contract, _) = setup_store(&vm, U256::from(1_000));
+        let store = String::from("store");
+        let timelock = 3_600;
+
+        assert!(contract
+            .set_payout_timelock(store.clone(), U256::from(timelock))
+            .is_ok());
+        let result = contract.set_payout_timelock(store.clone(), U256::ZERO);
+        assert!(matches!(
+            result,
+            Err(OnClickContractError::InvalidTimelock(_))
+        ));
+
+        assert!(contract.set_payout_address(store.clone(), PAYEE).is_ok());
+        assert!(matches!(
+            contract.get_payout_info(store.clone()),
+            Ok((payout, pending, _, _)) if payout == SELLER && pending == PAYEE
+        ));
+
+        vm.set_block_timestamp(REVEAL_AT + timelock - 1);
+        let result = contract.apply_payout_address(store.clone());
+        assert!(matches!(
+            result,
+            Err(OnClickContractError::PayoutTimelockActive(_))
+        ));
+
+        vm.set_block_timestamp(REVEAL_AT + timelock);
+        assert!(contract.apply_payout_address(store.clone()).is_ok());
+        assert!(matches!(
+            contract.get_payout_info(store),
+            Ok((payout, _, _, _)) if payout == PAYEE
+        ));
+    }
+
+    #[test]
+    fn revenue_split_change_waits_for_timelock() {
+        let vm = TestVM::default();
+        let (mut contract, product_id) = setup_store(&vm, U256::from(1_000));
+        let store = String::from("store");
+        let timelock = 3_600;
+
+        assert!(contract
+            .set_payout_timelock(store.clone(), U256::from(timelock))
+            .is_ok());
+        let result = contract.set_revenue_splits(
+            store.clone(),
+            vec![PAYEE],
+            vec![String::new()],
+            vec![U256::from(10_000)],
+        );
+        assert!(result.is_ok());
+        assert!(matches!(
+            contract.get_revenue_splits(store.clone()),
+            Ok(splits) if splits.is_empty()
+        ));
+
+        // Income keeps going to the owner until the change is applied
+        vm.set_sender(BUYER);
+        vm.set_value(U256::from(1_000));
+        assert!(purchase(&mut contract, product_id, U256::ZERO).is_ok());
+        assert_eq!(contract.pendingWithdrawals.get(SELLER), U256::from(1_000));
+        assert_eq!(contract.pendingWithdrawals.get(PAYEE), U256::ZERO);
+
+        vm.set_sender(SELLER);
+        vm.set_block_timestamp(REVEAL_AT + timelock - 1);
+        let result = contract.apply_revenue_splits(store.clone());
+        assert!(matches!(
+            result,
+            Err(OnClickContractError::PayoutTimelockActive(_))
+        ));
+
+        vm.set_block_timestamp(REVEAL_AT + timelock);
+        assert!(contract.apply_revenue_splits(store).is_ok());
+
+        vm.set_sender(BUYER);
+        assert!(purchase(&mut contract, product_id, U256::ZERO).is_ok());
+        assert_eq!(contract.pendingWithdrawals.get(PAYEE), U256::from(1_000));
     }
 }